@@ -0,0 +1,371 @@
+//! Audit a ROM collection for duplicates, bad dumps and wrong headers
+
+use {
+    super::{
+        checksum::{sha1, Sha1},
+        database::{Database, Entry},
+        parse_recover, Header, Missing, Overdump, ParseError, Vector, VectorProblem,
+    },
+    std::{
+        collections::HashMap,
+        fmt, fs, io,
+        path::{Path, PathBuf},
+    },
+};
+
+/// A file read by audit
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ScannedFile {
+    /// File path
+    pub path: PathBuf,
+    /// SHA-1 of whole file
+    pub sha1: [u8; 20],
+    /// SHA-1 of headerless data, `None` if header can't be parsed
+    pub data_sha1: Option<[u8; 20]>,
+    /// Parsed header, `None` if it can't be parsed
+    pub header: Option<Header>,
+}
+
+/// A problem found by audit
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Issue {
+    /// File can't be read
+    Unreadable {
+        /// File path
+        path: PathBuf,
+        /// Kind of the IO error
+        kind: io::ErrorKind,
+        /// Message of the IO error
+        message: String,
+    },
+    /// Header can't be parsed
+    Unparsable {
+        /// File path
+        path: PathBuf,
+        /// Why header is invalid
+        error: ParseError,
+    },
+    /// File has fewer bytes than header declared
+    Truncated {
+        /// File path
+        path: PathBuf,
+        /// Incomplete sections
+        missing: Vec<Missing>,
+    },
+    /// ROM sections are likely overdumped
+    Overdumped {
+        /// File path
+        path: PathBuf,
+        /// Overdumped sections
+        overdumps: Vec<Overdump>,
+    },
+    /// Reset vector is blank or doesn't point to PRG-ROM, usually a bad dump
+    BadResetVector {
+        /// File path
+        path: PathBuf,
+        /// What's wrong with the vector
+        problem: VectorProblem,
+    },
+    /// Files with exactly the same content
+    Duplicates {
+        /// Paths of the files
+        paths: Vec<PathBuf>,
+    },
+    /// Files with the same headerless data but different headers
+    SameData {
+        /// Paths of the files
+        paths: Vec<PathBuf>,
+    },
+    /// Header is different from the one in database
+    HeaderMismatch {
+        /// File path
+        path: PathBuf,
+        /// Game name in database
+        name: Option<String>,
+        /// Names of different header fields
+        fields: Vec<&'static str>,
+    },
+    /// Database marks the game as a hack
+    Hack {
+        /// File path
+        path: PathBuf,
+        /// Game name in database
+        name: String,
+    },
+    /// Database marks the game as a fan translation
+    Translation {
+        /// File path
+        path: PathBuf,
+        /// Game name in database
+        name: String,
+    },
+    /// Database marks the game as a bad dump
+    BadDump {
+        /// File path
+        path: PathBuf,
+        /// Game name in database
+        name: String,
+    },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |paths: &[PathBuf]| {
+            paths.iter().map(|x| x.display().to_string()).collect::<Vec<_>>().join(", ")
+        };
+        match self {
+            Self::Unreadable { path, message, .. } => {
+                write!(f, "{}: can't be read, {message}", path.display())
+            }
+            Self::Unparsable { path, error } => write!(f, "{}: {error}", path.display()),
+            Self::Truncated { path, missing } => {
+                let count: usize = missing.iter().map(Missing::count).sum();
+                write!(f, "{}: truncated, {count} bytes missing", path.display())
+            }
+            Self::Overdumped { path, overdumps } => {
+                write!(f, "{}: overdumped", path.display())?;
+                for x in overdumps {
+                    write!(f, ", {} should be {} bytes", x.section, x.suggested)?;
+                }
+                Ok(())
+            }
+            Self::BadResetVector { path, problem } => write!(f, "{}: {problem}", path.display()),
+            Self::Duplicates { paths } => write!(f, "duplicates: {}", join(paths)),
+            Self::SameData { paths } => {
+                write!(f, "same data with different headers: {}", join(paths))
+            }
+            Self::HeaderMismatch { path, name, fields } => {
+                write!(
+                    f,
+                    "{}: header differs from database in {}",
+                    path.display(),
+                    fields.join(", ")
+                )?;
+                if let Some(name) = name {
+                    write!(f, " ({name})")?;
+                }
+                Ok(())
+            }
+            Self::Hack { path, name } => write!(f, "{}: hack ({name})", path.display()),
+            Self::Translation { path, name } => {
+                write!(f, "{}: translation ({name})", path.display())
+            }
+            Self::BadDump { path, name } => {
+                write!(f, "{}: known bad dump ({name})", path.display())
+            }
+        }
+    }
+}
+
+/// Result of [`audit`](fn.audit.html)
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Audit {
+    /// All files which are read, in the order they are given
+    pub files: Vec<ScannedFile>,
+    /// Problems found, problems of single files come first in file order, then groups of files
+    pub issues: Vec<Issue>,
+}
+
+/// Audit files given as paths and contents, compare with `database` if provided.
+///
+/// # Examples
+///
+/// ```rust
+/// use dotnes::Issue;
+///
+/// let data = std::fs::read("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+/// let files = vec![("a.nes".into(), data.clone()), ("b.nes".into(), data)];
+/// let audit = dotnes::audit(files, None);
+/// assert_eq!(audit.issues, [Issue::Duplicates { paths: vec!["a.nes".into(), "b.nes".into()] }]);
+/// ```
+pub fn audit<I>(files: I, database: Option<&Database>) -> Audit
+where
+    I: IntoIterator<Item = (PathBuf, Vec<u8>)>,
+{
+    audit_results(files.into_iter().map(|(path, data)| (path, Ok(data))), database)
+}
+
+/// Audit files which are read one by one, files failed to read are reported as
+/// [`Issue::Unreadable`](enum.Issue.html#variant.Unreadable).
+fn audit_results<I>(files: I, database: Option<&Database>) -> Audit
+where
+    I: IntoIterator<Item = (PathBuf, io::Result<Vec<u8>>)>,
+{
+    let mut result = Audit::default();
+
+    for (path, data) in files {
+        let data = match data {
+            Ok(data) => data,
+            Err(err) => {
+                let (kind, message) = (err.kind(), err.to_string());
+                result.issues.push(Issue::Unreadable { path, kind, message });
+                continue;
+            }
+        };
+        let (file, issues) = scan(path, &data, database);
+        result.files.push(file);
+        result.issues.extend(issues);
+    }
+
+    let duplicates = groups(&result.files, |x| Some(x.sha1));
+    let same_data = groups(&result.files, |x| x.data_sha1)
+        .into_iter()
+        .filter(|group| group.iter().any(|x| x.sha1 != group[0].sha1));
+
+    let paths = |group: Vec<&ScannedFile>| group.into_iter().map(|x| x.path.clone()).collect();
+    let duplicates: Vec<_> =
+        duplicates.into_iter().map(|x| Issue::Duplicates { paths: paths(x) }).collect();
+    let same_data: Vec<_> = same_data.map(|x| Issue::SameData { paths: paths(x) }).collect();
+    result.issues.extend(duplicates);
+    result.issues.extend(same_data);
+
+    result
+}
+
+/// Audit all `.nes` files in directory `dir` recursively, see [`audit`](fn.audit.html).
+///
+/// Files are read one at a time, a file which can't be read is reported as
+/// [`Issue::Unreadable`](enum.Issue.html#variant.Unreadable).
+///
+/// # Errors
+///
+/// When a directory can't be read, return the IO error.
+pub fn audit_dir<P: AsRef<Path>>(dir: P, database: Option<&Database>) -> io::Result<Audit> {
+    let mut paths = Vec::new();
+    collect_nes_files(dir.as_ref(), &mut paths)?;
+    paths.sort();
+
+    let files = paths.into_iter().map(|path| {
+        let data = fs::read(&path);
+        (path, data)
+    });
+
+    Ok(audit_results(files, database))
+}
+
+fn collect_nes_files(dir: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_nes_files(&path, paths)?;
+        } else if path.extension().is_some_and(|x| x.eq_ignore_ascii_case("nes")) {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn scan(path: PathBuf, data: &[u8], database: Option<&Database>) -> (ScannedFile, Vec<Issue>) {
+    let mut file = ScannedFile { path, sha1: sha1(data), data_sha1: None, header: None };
+    let mut issues = Vec::new();
+
+    let recovered = match parse_recover(data) {
+        Ok(recovered) => recovered,
+        Err(error) => {
+            issues.push(Issue::Unparsable { path: file.path.clone(), error });
+            return (file, issues);
+        }
+    };
+
+    let mut data_sha1 = Sha1::new();
+    for section in &recovered.file.headerless() {
+        data_sha1.update(section);
+    }
+    file.data_sha1 = Some(data_sha1.finish());
+    file.header = Some(recovered.file.header.clone());
+
+    let path = || file.path.clone();
+    if !recovered.missing.is_empty() {
+        issues.push(Issue::Truncated { path: path(), missing: recovered.missing.clone() });
+    }
+    if !recovered.overdumps.is_empty() {
+        issues.push(Issue::Overdumped { path: path(), overdumps: recovered.overdumps.clone() });
+    }
+    let vectors = recovered.file.vectors();
+    let problems = vectors.map(|x| x.problems(&recovered.file)).unwrap_or_default();
+    if let Some(&problem) = problems.iter().find(|x| x.vector() == Vector::Reset) {
+        issues.push(Issue::BadResetVector { path: path(), problem });
+    }
+
+    if let Some(entry) = database.and_then(|x| x.identify_bytes(data)) {
+        issues.extend(database_issues(&file.path, entry, &recovered.file.header));
+    }
+
+    (file, issues)
+}
+
+/// Issues of a file the database identifies as `entry`, every flag of its name is reported
+fn database_issues(path: &Path, entry: &Entry, header: &Header) -> Vec<Issue> {
+    let path = || path.to_path_buf();
+    let mut issues = Vec::new();
+    if let Some(expected) = &entry.header {
+        let fields = header_differences(expected, header);
+        if !fields.is_empty() {
+            issues.push(Issue::HeaderMismatch { path: path(), name: entry.name.clone(), fields });
+        }
+    }
+    if let (Some(name), Some(parsed)) = (&entry.name, entry.game_name()) {
+        if parsed.is_hack() {
+            issues.push(Issue::Hack { path: path(), name: name.clone() });
+        }
+        if parsed.is_translation() {
+            issues.push(Issue::Translation { path: path(), name: name.clone() });
+        }
+        if parsed.is_bad_dump() {
+            issues.push(Issue::BadDump { path: path(), name: name.clone() });
+        }
+    }
+    issues
+}
+
+/// Group files by `key`, only groups with more than one file are returned, in file order
+fn groups<K, F>(files: &[ScannedFile], key: F) -> Vec<Vec<&ScannedFile>>
+where
+    K: Eq + std::hash::Hash,
+    F: Fn(&ScannedFile) -> Option<K>,
+{
+    let mut index = HashMap::new();
+    let mut groups: Vec<Vec<&ScannedFile>> = Vec::new();
+    for file in files {
+        if let Some(key) = key(file) {
+            let i = *index.entry(key).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[i].push(file);
+        }
+    }
+    groups.retain(|x| x.len() > 1);
+    groups
+}
+
+/// Names of header fields which are different, fields iNES format can't store are only
+/// compared when both headers are NES 2.0
+fn header_differences(expected: &Header, found: &Header) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+    let mut compare = |name, different| {
+        if different {
+            fields.push(name);
+        }
+    };
+
+    compare("mapper", expected.mapper != found.mapper);
+    compare("PRG-ROM size", expected.prg_rom_size != found.prg_rom_size);
+    compare("CHR-ROM size", expected.chr_rom_size != found.chr_rom_size);
+    compare("mirroring", expected.mirroring != found.mirroring);
+    compare("four-screen", expected.is_four_screen != found.is_four_screen);
+    compare("battery", expected.has_persistent_memory != found.has_persistent_memory);
+    compare("trainer", expected.has_trainer != found.has_trainer);
+
+    if expected.is_nes2 && found.is_nes2 {
+        compare("submapper", expected.sub_mapper != found.sub_mapper);
+        compare("PRG-RAM size", expected.prg_ram_size != found.prg_ram_size);
+        compare("PRG-NVRAM size", expected.prg_nvram_size != found.prg_nvram_size);
+        compare("CHR-RAM size", expected.chr_ram_size != found.chr_ram_size);
+        compare("CHR-NVRAM size", expected.chr_nvram_size != found.chr_nvram_size);
+        compare("timing", expected.timing != found.timing);
+        compare("console type", expected.console_type != found.console_type);
+    }
+
+    fields
+}
@@ -0,0 +1,192 @@
+//! Split PRG-ROM and CHR-ROM into banks
+
+use {
+    super::chr::{self, Tiles},
+    std::{error::Error, fmt, iter::Enumerate, slice::ChunksExact},
+};
+
+/// Size of a switchable bank
+#[allow(missing_docs)] // because the variant name is clear enough
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum BankSize {
+    Size1K,
+    Size2K,
+    Size4K,
+    Size8K,
+    Size16K,
+    Size32K,
+}
+
+impl BankSize {
+    /// Bank size in bytes
+    #[must_use]
+    pub const fn bytes(self) -> usize {
+        match self {
+            Self::Size1K => 0x400,
+            Self::Size2K => 0x800,
+            Self::Size4K => 0x1000,
+            Self::Size8K => 0x2000,
+            Self::Size16K => 0x4000,
+            Self::Size32K => 0x8000,
+        }
+    }
+
+    /// Get bank size from bytes count, `None` if it's not a supported size
+    #[must_use]
+    pub const fn from_bytes(bytes: usize) -> Option<Self> {
+        Some(match bytes {
+            0x400 => Self::Size1K,
+            0x800 => Self::Size2K,
+            0x1000 => Self::Size4K,
+            0x2000 => Self::Size8K,
+            0x4000 => Self::Size16K,
+            0x8000 => Self::Size32K,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for BankSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} KiB", self.bytes() >> 10)
+    }
+}
+
+/// Split ROM into banks failed reason
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum BankError {
+    /// ROM size is not a multiple of bank size
+    NotMultiple {
+        /// ROM size in bytes
+        rom_size: usize,
+        /// Requested bank size
+        bank_size: BankSize,
+    },
+    /// ROM is empty, so there is no bank at all
+    Empty,
+    /// Bank number is not less than bank count
+    OutOfRange {
+        /// Requested bank number
+        index: usize,
+        /// Bank count of the ROM
+        count: usize,
+    },
+    /// Data to put in a bank is not the bank size
+    WrongSize {
+        /// Data size in bytes
+        size: usize,
+        /// Bank size
+        bank_size: BankSize,
+    },
+    /// Data doesn't have the length of the file its banks are located by
+    FileMismatch {
+        /// Data size in bytes
+        size: usize,
+        /// File size in bytes
+        file_len: usize,
+    },
+}
+
+impl fmt::Display for BankError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotMultiple { rom_size, bank_size } => {
+                write!(f, "ROM size {rom_size} is not a multiple of bank size {bank_size}")
+            }
+            Self::Empty => f.write_str("ROM is empty"),
+            Self::OutOfRange { index, count } => {
+                write!(f, "bank {index} is out of range, ROM has {count} banks")
+            }
+            Self::WrongSize { size, bank_size } => {
+                write!(f, "data size {size} is not the bank size {bank_size}")
+            }
+            Self::FileMismatch { size, file_len } => {
+                write!(f, "data size {size} doesn't match the parsed file size {file_len}")
+            }
+        }
+    }
+}
+
+impl Error for BankError {}
+
+/// A bank of ROM data
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Bank<'a> {
+    /// Bank number, starts from 0
+    pub index: usize,
+    /// Bank content
+    pub data: &'a [u8],
+}
+
+impl<'a> Bank<'a> {
+    /// Offset of this bank in the ROM it belongs to
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.index * self.data.len()
+    }
+
+    /// Iterate 8×8 tiles of this bank, for CHR-ROM banks.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dotnes::{Bank, chr::Tile};
+    ///
+    /// let bank = Bank { index: 0, data: &[0; 0x400] };
+    /// assert_eq!(bank.tiles().count(), 64);
+    /// assert!(bank.tiles().all(|tile| tile == Tile::default()));
+    /// ```
+    #[must_use]
+    pub fn tiles(&self) -> Tiles<'a> {
+        chr::tiles(self.data)
+    }
+}
+
+/// Iterator over banks of a ROM, created by [`NESFile::prg_banks`] or [`NESFile::chr_banks`]
+///
+/// [`NESFile::prg_banks`]: struct.NESFile.html#method.prg_banks
+/// [`NESFile::chr_banks`]: struct.NESFile.html#method.chr_banks
+#[derive(Debug, Clone)]
+pub struct Banks<'a> {
+    inner: Enumerate<ChunksExact<'a, u8>>,
+}
+
+impl<'a> Banks<'a> {
+    /// Split `rom` into banks of `size`.
+    ///
+    /// # Errors
+    ///
+    /// When `rom` size is not a multiple of `size`, return
+    /// Err([`BankError::NotMultiple`](enum.BankError.html#variant.NotMultiple)).
+    pub fn new(rom: &'a [u8], size: BankSize) -> Result<Self, BankError> {
+        if rom.len() % size.bytes() != 0 {
+            return Err(BankError::NotMultiple { rom_size: rom.len(), bank_size: size });
+        }
+        Ok(Self { inner: rom.chunks_exact(size.bytes()).enumerate() })
+    }
+}
+
+impl<'a> Iterator for Banks<'a> {
+    type Item = Bank<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(index, data)| Bank { index, data })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Banks<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(index, data)| Bank { index, data })
+    }
+}
+
+impl ExactSizeIterator for Banks<'_> {}
+
+/// Get the last bank of `rom`, which is the fixed bank of most mappers.
+pub fn last_bank(rom: &[u8], size: BankSize) -> Result<Bank<'_>, BankError> {
+    Banks::new(rom, size)?.next_back().ok_or(BankError::Empty)
+}
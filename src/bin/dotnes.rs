@@ -0,0 +1,328 @@
+//! Command line tool of dotnes.
+//!
+//! ```text
+//! dotnes audit [--database FILE]... DIR
+//! dotnes disasm [--entry ADDR]... FILE
+//! dotnes cdl [--output FILE] FILE LOG...
+//! dotnes genie FILE OUTPUT CODE...
+//! dotnes chr [--palette FILE] [--colors 0F,00,10,30] [--bank N] FILE PREFIX
+//! dotnes chr-import [--palette FILE] [--colors 0F,00,10,30] [--bank N] FILE IMAGE OUTPUT
+//! ```
+//!
+//! `audit` checks a ROM collection, database files can be NES 2.0 XML database or
+//! No-Intro/`GoodNES` DAT files.
+//!
+//! `disasm` traces code from the vectors, or the hexadecimal entry addresses if given, and prints
+//! ca65 source of the fixed bank.
+//!
+//! `cdl` merges FCEUX Code/Data Logger files `LOG` of NES file `FILE`, prints coverage of each
+//! 16 KiB PRG-ROM bank and 8 KiB CHR-ROM bank, or the whole ROM if it's smaller, and writes the
+//! merged log to `--output` if given.
+//!
+//! `genie` applies Game Genie codes to NES file `FILE` and writes it to `OUTPUT`.
+//!
+//! `chr` writes each 4 KiB pattern table of CHR-ROM to `PREFIX0.png`, `PREFIX1.png`, etc, or only
+//! table `N` if given. `chr-import` converts PNG image `IMAGE` back to CHR data, replaces bank `N`
+//! (0 by default) of the image size, and writes the new NES file to `OUTPUT`. Colors are PPU color
+//! indices looked up in the palette of the PPU the header declares, or the `.pal` file if given.
+//! Both need the `png` feature.
+
+#![deny(clippy::all, clippy::pedantic, clippy::nursery)]
+#![deny(missing_debug_implementations, missing_docs, rust_2018_idioms)]
+#![deny(warnings)]
+
+use {
+    dotnes::{
+        cdl::CodeDataLog,
+        database::Database,
+        disasm,
+        gamegenie::{self, Code},
+        BankSize,
+    },
+    std::{env, fs, path::PathBuf, process},
+};
+
+#[cfg(feature = "png")]
+use {
+    dotnes::{
+        chr::Sheet,
+        header::Header,
+        palette::{Palette, Rgb},
+    },
+    std::{convert::TryFrom, io},
+};
+
+const USAGE: &str = "usage:
+    dotnes audit [--database FILE]... DIR
+    dotnes disasm [--entry ADDR]... FILE
+    dotnes cdl [--output FILE] FILE LOG...
+    dotnes genie FILE OUTPUT CODE...
+    dotnes chr [--palette FILE] [--colors 0F,00,10,30] [--bank N] FILE PREFIX
+    dotnes chr-import [--palette FILE] [--colors 0F,00,10,30] [--bank N] FILE IMAGE OUTPUT";
+
+fn load_database(path: &str) -> Result<Database, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    let database = if text.contains("<nes20db") {
+        Database::from_nes20db(&text)
+    } else {
+        Database::from_dat(&text)
+    };
+    database.map_err(|e| format!("{path}: {e}"))
+}
+
+fn audit(args: &[String]) -> Result<(), String> {
+    let mut database: Option<Database> = None;
+    let mut dir: Option<PathBuf> = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--database" {
+            let path = args.next().ok_or_else(|| USAGE.to_owned())?;
+            let loaded = load_database(path)?;
+            database.get_or_insert_with(Database::default).entries.extend(loaded.entries);
+        } else if dir.is_none() {
+            dir = Some(arg.into());
+        } else {
+            return Err(USAGE.to_owned());
+        }
+    }
+
+    let dir = dir.ok_or_else(|| USAGE.to_owned())?;
+    let result = dotnes::audit_dir(&dir, database.as_ref())
+        .map_err(|e| format!("{}: {e}", dir.display()))?;
+
+    for issue in &result.issues {
+        println!("{issue}");
+    }
+    println!("{} files, {} issues", result.files.len(), result.issues.len());
+    Ok(())
+}
+
+fn disasm(args: &[String]) -> Result<(), String> {
+    let mut entries = Vec::new();
+    let mut file = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--entry" {
+            let text = args.next().ok_or_else(|| USAGE.to_owned())?;
+            let hex = text.trim_start_matches('$');
+            entries.push(
+                u16::from_str_radix(hex, 16).map_err(|_| format!("invalid address: {text}"))?,
+            );
+        } else if file.is_none() {
+            file = Some(arg);
+        } else {
+            return Err(USAGE.to_owned());
+        }
+    }
+    let file = file.ok_or_else(|| USAGE.to_owned())?;
+
+    let data = fs::read(file).map_err(|e| format!("{file}: {e}"))?;
+    let nes = dotnes::parse(&data).map_err(|e| format!("{file}: {e}"))?;
+    let trace =
+        if entries.is_empty() { disasm::trace(&nes) } else { disasm::trace_from(&nes, &entries) };
+    print!("{}", trace.to_ca65(&nes));
+    Ok(())
+}
+
+fn cdl(args: &[String]) -> Result<(), String> {
+    let mut output = None;
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--output" {
+            output = Some(args.next().ok_or_else(|| USAGE.to_owned())?);
+        } else {
+            paths.push(arg);
+        }
+    }
+    let Some((file, logs)) = paths.split_first().filter(|(_, logs)| !logs.is_empty()) else {
+        return Err(USAGE.to_owned());
+    };
+
+    let data = fs::read(file).map_err(|e| format!("{file}: {e}"))?;
+    let nes = dotnes::parse(&data).map_err(|e| format!("{file}: {e}"))?;
+    let mut merged = CodeDataLog::new(&nes);
+    for path in logs {
+        let data = fs::read(path).map_err(|e| format!("{path}: {e}"))?;
+        let log = CodeDataLog::parse(&data, &nes).map_err(|e| format!("{path}: {e}"))?;
+        merged.merge(&log).map_err(|e| format!("{path}: {e}"))?;
+    }
+
+    let percent = |count: usize, bytes: usize| count * 100 / bytes;
+    let size = coverage_bank_size(nes.prg_rom.len(), BankSize::Size16K);
+    let prg = merged.prg_coverage(size).map_err(|e| format!("{file}: {e}"))?;
+    for x in prg {
+        let (code, data, logged) =
+            (percent(x.code, x.bytes), percent(x.data, x.bytes), percent(x.logged, x.bytes));
+        println!("PRG {}: {logged}% logged, {code}% code, {data}% data", x.bank);
+    }
+    let size = coverage_bank_size(nes.chr_rom.len(), BankSize::Size8K);
+    let chr = merged.chr_coverage(size).map_err(|e| format!("{file}: {e}"))?;
+    for x in chr {
+        let (rendered, read, logged) =
+            (percent(x.rendered, x.bytes), percent(x.read, x.bytes), percent(x.logged, x.bytes));
+        println!("CHR {}: {logged}% logged, {rendered}% rendered, {read}% read", x.bank);
+    }
+
+    if let Some(output) = output {
+        fs::write(output, merged.to_bytes()).map_err(|e| format!("{output}: {e}"))?;
+    }
+    Ok(())
+}
+
+/// `max`, or ROM size if it's smaller, like 8 KiB PRG-ROM of some NES 2.0 files
+fn coverage_bank_size(rom_size: usize, max: BankSize) -> BankSize {
+    BankSize::from_bytes(usize::min(rom_size, max.bytes())).unwrap_or(max)
+}
+
+fn genie(args: &[String]) -> Result<(), String> {
+    let [file, output, codes @ ..] = args else {
+        return Err(USAGE.to_owned());
+    };
+    if codes.is_empty() {
+        return Err(USAGE.to_owned());
+    }
+    let codes = codes
+        .iter()
+        .map(|x| x.parse::<Code>().map_err(|e| format!("{x}: {e}")))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut data = fs::read(file).map_err(|e| format!("{file}: {e}"))?;
+    let offsets = gamegenie::apply(&mut data, &codes).map_err(|e| format!("{file}: {e}"))?;
+    println!("{} bytes changed", offsets.len());
+    fs::write(output, data).map_err(|e| format!("{output}: {e}"))
+}
+
+#[cfg(feature = "png")]
+fn parse_colors(text: &str) -> Result<[u8; 4], String> {
+    let invalid = || format!("invalid colors: {text}");
+    let indices = text
+        .split(',')
+        .map(|x| u8::from_str_radix(x.trim(), 16).ok().filter(|&x| x < 0x40))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(invalid)?;
+    <[u8; 4]>::try_from(indices).map_err(|_| invalid())
+}
+
+/// Options of `chr` and `chr-import`
+#[cfg(feature = "png")]
+#[derive(Debug)]
+struct ChrOptions<'a> {
+    palette: Option<Palette>,
+    indices: [u8; 4],
+    bank: Option<usize>,
+    paths: Vec<&'a str>,
+}
+
+#[cfg(feature = "png")]
+impl<'a> ChrOptions<'a> {
+    fn parse(args: &'a [String]) -> Result<Self, String> {
+        let mut palette = None;
+        let mut indices = [0x0F, 0x00, 0x10, 0x30];
+        let mut bank = None;
+        let mut paths = Vec::new();
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| USAGE.to_owned());
+            match arg.as_str() {
+                "--palette" => {
+                    let path = value()?;
+                    let data = fs::read(path).map_err(|e| format!("{path}: {e}"))?;
+                    palette = Some(Palette::from_pal(&data).map_err(|e| format!("{path}: {e}"))?);
+                }
+                "--colors" => indices = parse_colors(value()?)?,
+                "--bank" => {
+                    let text = value()?;
+                    bank = Some(text.parse().map_err(|_| format!("invalid bank: {text}"))?);
+                }
+                _ => paths.push(arg.as_str()),
+            }
+        }
+
+        Ok(Self { palette, indices, bank, paths })
+    }
+
+    /// Colors of the selected indices, in the `.pal` file or the palette `header` declares
+    fn colors(&self, header: &Header) -> [Rgb; 4] {
+        self.palette
+            .as_ref()
+            .map_or_else(|| Palette::for_header(header), Clone::clone)
+            .select(self.indices)
+    }
+}
+
+#[cfg(feature = "png")]
+fn chr(args: &[String]) -> Result<(), String> {
+    let options = ChrOptions::parse(args)?;
+    let [file, prefix] = options.paths[..] else {
+        return Err(USAGE.to_owned());
+    };
+
+    let data = fs::read(file).map_err(|e| format!("{file}: {e}"))?;
+    let nes = dotnes::parse(&data).map_err(|e| format!("{file}: {e}"))?;
+    if nes.chr_rom.is_empty() {
+        return Err(format!("{file}: no CHR-ROM, the game uses CHR-RAM"));
+    }
+    for (i, sheet) in dotnes::chr::pattern_tables(nes.chr_rom).iter().enumerate() {
+        if options.bank.is_some_and(|bank| bank != i) {
+            continue;
+        }
+        let path = format!("{prefix}{i}.png");
+        let output = fs::File::create(&path).map_err(|e| format!("{path}: {e}"))?;
+        sheet
+            .write_png(&options.colors(&nes.header), io::BufWriter::new(output))
+            .map_err(|e| format!("{path}: {e}"))?;
+        println!("{path}");
+    }
+    Ok(())
+}
+
+#[cfg(feature = "png")]
+fn chr_import(args: &[String]) -> Result<(), String> {
+    let options = ChrOptions::parse(args)?;
+    let [file, image, output] = options.paths[..] else {
+        return Err(USAGE.to_owned());
+    };
+
+    let data = fs::read(file).map_err(|e| format!("{file}: {e}"))?;
+    let nes = dotnes::parse(&data).map_err(|e| format!("{file}: {e}"))?;
+    let input = fs::File::open(image).map_err(|e| format!("{image}: {e}"))?;
+    let sheet = Sheet::read_png(io::BufReader::new(input), &options.colors(&nes.header))
+        .map_err(|e| format!("{image}: {e}"))?;
+
+    let chr = sheet.to_chr();
+    let size = BankSize::from_bytes(chr.len())
+        .ok_or_else(|| format!("{image}: {} bytes of CHR is not a bank size", chr.len()))?;
+    let bytes = dotnes::chr::replace_bank(&data, &nes, size, options.bank.unwrap_or(0), &chr)
+        .map_err(|e| format!("{file}: {e}"))?;
+    fs::write(output, bytes).map_err(|e| format!("{output}: {e}"))
+}
+
+#[cfg(not(feature = "png"))]
+fn chr(_: &[String]) -> Result<(), String> {
+    Err("dotnes is built without the png feature".to_owned())
+}
+
+#[cfg(not(feature = "png"))]
+fn chr_import(_: &[String]) -> Result<(), String> {
+    chr(&[])
+}
+
+fn main() {
+    let args: Vec<_> = env::args().skip(1).collect();
+    let result = match args.split_first() {
+        Some((command, rest)) if command == "audit" => audit(rest),
+        Some((command, rest)) if command == "disasm" => disasm(rest),
+        Some((command, rest)) if command == "cdl" => cdl(rest),
+        Some((command, rest)) if command == "genie" => genie(rest),
+        Some((command, rest)) if command == "chr" => chr(rest),
+        Some((command, rest)) if command == "chr-import" => chr_import(rest),
+        _ => Err(USAGE.to_owned()),
+    };
+    if let Err(message) = result {
+        eprintln!("{message}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,340 @@
+//! FCEUX Code/Data Logger files.
+//!
+//! A `.cdl` file has one flag byte for each PRG-ROM byte, followed by one for each CHR-ROM byte.
+//! The CHR-ROM part is missing when the game uses CHR-RAM.
+
+use {
+    crate::{
+        disasm::{ByteKind, Trace},
+        BankError, BankSize, NESFile,
+    },
+    std::{error::Error, fmt, ops::BitOr},
+};
+
+/// Flags of a PRG-ROM byte
+///
+/// Bits 2 - 3 are the 8KB window the byte was accessed through, 0 for $8000 to 3 for $E000.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct PrgFlags(pub u8);
+
+impl PrgFlags {
+    /// Executed as code
+    pub const CODE: Self = Self(0x01);
+    /// Read as data
+    pub const DATA: Self = Self(0x02);
+    /// Code reached by an indirect jump
+    pub const INDIRECT_CODE: Self = Self(0x10);
+    /// Data read by indirect addressing
+    pub const INDIRECT_DATA: Self = Self(0x20);
+    /// Read by the APU as DPCM sample
+    pub const PCM: Self = Self(0x40);
+
+    const WINDOW: u8 = 0x0C;
+
+    /// If all flags in `other` are set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dotnes::cdl::PrgFlags;
+    ///
+    /// let flags = PrgFlags::CODE | PrgFlags::INDIRECT_CODE;
+    /// assert!(flags.contains(PrgFlags::CODE));
+    /// assert!(!flags.contains(PrgFlags::DATA));
+    /// ```
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// If the byte is logged at all.
+    #[must_use]
+    pub const fn is_logged(self) -> bool {
+        self.0 & (Self::CODE.0 | Self::DATA.0) != 0
+    }
+
+    /// CPU address of the 8KB window the byte was accessed through, $8000, $A000, $C000 or $E000.
+    #[must_use]
+    pub const fn window(self) -> u16 {
+        0x8000 + ((self.0 & Self::WINDOW) as u16 >> 2) * 0x2000
+    }
+
+    /// Set the window to the one containing CPU `address`, which is $8000 or above.
+    #[must_use]
+    pub const fn with_window(self, address: u16) -> Self {
+        #[allow(clippy::cast_possible_truncation)] // 2 bits
+        let window = ((address >> 13) & 3) as u8;
+        Self(self.0 & !Self::WINDOW | window << 2)
+    }
+}
+
+impl BitOr for PrgFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// Flags of a CHR-ROM byte
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct ChrFlags(pub u8);
+
+impl ChrFlags {
+    /// Fetched by the PPU for rendering
+    pub const RENDERED: Self = Self(0x01);
+    /// Read by the CPU through $2007
+    pub const READ: Self = Self(0x02);
+
+    /// If all flags in `other` are set.
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// If the byte is logged at all.
+    #[must_use]
+    pub const fn is_logged(self) -> bool {
+        self.0 & (Self::RENDERED.0 | Self::READ.0) != 0
+    }
+}
+
+impl BitOr for ChrFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// Why a CDL file doesn't fit
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CdlError {
+    /// File size is neither PRG-ROM size nor PRG-ROM plus CHR-ROM size
+    WrongSize {
+        /// CDL file size
+        size: usize,
+        /// PRG-ROM size of the NES file
+        prg_rom_size: usize,
+        /// CHR-ROM size of the NES file
+        chr_rom_size: usize,
+    },
+    /// Logs to merge are for ROMs with different sizes
+    DifferentSizes,
+}
+
+impl fmt::Display for CdlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongSize { size, prg_rom_size, chr_rom_size } => write!(
+                f,
+                "CDL size {size} doesn't match PRG-ROM size {prg_rom_size} and CHR-ROM size \
+                 {chr_rom_size}"
+            ),
+            Self::DifferentSizes => f.write_str("CDL files are for different ROM sizes"),
+        }
+    }
+}
+
+impl Error for CdlError {}
+
+/// Coverage of a PRG-ROM bank
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct PrgCoverage {
+    /// Bank number
+    pub bank: usize,
+    /// Bytes in the bank
+    pub bytes: usize,
+    /// Bytes logged as code
+    pub code: usize,
+    /// Bytes logged as data
+    pub data: usize,
+    /// Bytes logged as code or data
+    pub logged: usize,
+}
+
+/// Coverage of a CHR-ROM bank
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ChrCoverage {
+    /// Bank number
+    pub bank: usize,
+    /// Bytes in the bank
+    pub bytes: usize,
+    /// Bytes logged as rendered
+    pub rendered: usize,
+    /// Bytes logged as read by the CPU
+    pub read: usize,
+    /// Bytes logged as rendered or read
+    pub logged: usize,
+}
+
+/// Code/Data Logger flags aligned with PRG-ROM and CHR-ROM of a NES file
+///
+/// # Examples
+///
+/// ```rust
+/// use dotnes::cdl::{CodeDataLog, PrgFlags};
+///
+/// let data = std::fs::read("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+/// let nes = dotnes::parse(&data).unwrap();
+///
+/// let mut cdl = vec![0; nes.prg_rom.len()];
+/// cdl[0x2124] = 0x0D;
+/// let log = CodeDataLog::parse(&cdl, &nes).unwrap();
+/// assert!(log.prg[0x2124].contains(PrgFlags::CODE));
+/// assert_eq!(log.prg[0x2124].window(), 0xE000);
+/// assert_eq!(log.to_bytes(), cdl);
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct CodeDataLog {
+    /// Flags of each PRG-ROM byte
+    pub prg: Vec<PrgFlags>,
+    /// Flags of each CHR-ROM byte, empty if the game uses CHR-RAM
+    pub chr: Vec<ChrFlags>,
+}
+
+impl CodeDataLog {
+    /// Empty log for `file`.
+    #[must_use]
+    pub fn new(file: &NESFile<'_>) -> Self {
+        Self {
+            prg: vec![PrgFlags::default(); file.prg_rom.len()],
+            chr: vec![ChrFlags::default(); file.chr_rom.len()],
+        }
+    }
+
+    /// Parse CDL file `data` for `file`. The CHR-ROM part may be missing, then its flags are
+    /// all empty.
+    ///
+    /// # Errors
+    ///
+    /// Return [`CdlError::WrongSize`](enum.CdlError.html#variant.WrongSize) if the size doesn't
+    /// match ROM sizes of `file`.
+    pub fn parse(data: &[u8], file: &NESFile<'_>) -> Result<Self, CdlError> {
+        let prg_rom_size = file.prg_rom.len();
+        let chr_rom_size = file.chr_rom.len();
+        if data.len() != prg_rom_size && data.len() != prg_rom_size + chr_rom_size {
+            return Err(CdlError::WrongSize { size: data.len(), prg_rom_size, chr_rom_size });
+        }
+
+        let (prg, chr) = data.split_at(prg_rom_size);
+        let mut log = Self::new(file);
+        for (flags, &byte) in log.prg.iter_mut().zip(prg) {
+            *flags = PrgFlags(byte);
+        }
+        for (flags, &byte) in log.chr.iter_mut().zip(chr) {
+            *flags = ChrFlags(byte);
+        }
+        Ok(log)
+    }
+
+    /// Write the log in CDL format.
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let prg = self.prg.iter().map(|x| x.0);
+        prg.chain(self.chr.iter().map(|x| x.0)).collect()
+    }
+
+    /// Log of `trace` result, code and data found are flagged with the window of their
+    /// address.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dotnes::{cdl::{CodeDataLog, PrgFlags}, disasm};
+    ///
+    /// let data = std::fs::read("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+    /// let nes = dotnes::parse(&data).unwrap();
+    /// let log = CodeDataLog::from_trace(&disasm::trace(&nes), &nes);
+    /// assert_eq!(log.prg[0x2124], PrgFlags::CODE.with_window(0xE124));
+    /// ```
+    #[must_use]
+    pub fn from_trace(trace: &Trace, file: &NESFile<'_>) -> Self {
+        let mut log = Self::new(file);
+        for (offset, (flags, kind)) in log.prg.iter_mut().zip(&trace.kinds).enumerate() {
+            let new = match kind {
+                ByteKind::Code => PrgFlags::CODE,
+                ByteKind::Data => PrgFlags::DATA,
+                ByteKind::Unknown => continue,
+            };
+            *flags = new.with_window(trace.address(offset).unwrap_or_default());
+        }
+        log
+    }
+
+    /// Add flags of `other` log of the same ROM. The window bits are kept if the byte is already
+    /// logged.
+    ///
+    /// # Errors
+    ///
+    /// Return [`CdlError::DifferentSizes`](enum.CdlError.html#variant.DifferentSizes) if the logs
+    /// are for different ROM sizes, nothing is merged then.
+    pub fn merge(&mut self, other: &Self) -> Result<(), CdlError> {
+        if self.prg.len() != other.prg.len() || self.chr.len() != other.chr.len() {
+            return Err(CdlError::DifferentSizes);
+        }
+        for (flags, &new) in self.prg.iter_mut().zip(&other.prg) {
+            *flags = if flags.is_logged() {
+                *flags | PrgFlags(new.0 & !PrgFlags::WINDOW)
+            } else {
+                *flags | new
+            };
+        }
+        for (flags, &new) in self.chr.iter_mut().zip(&other.chr) {
+            *flags = *flags | new;
+        }
+        Ok(())
+    }
+
+    /// Coverage of each PRG-ROM bank of `size`.
+    ///
+    /// # Errors
+    ///
+    /// Return [`BankError`](../enum.BankError.html) if PRG-ROM size is not a multiple of
+    /// `size`.
+    pub fn prg_coverage(&self, size: BankSize) -> Result<Vec<PrgCoverage>, BankError> {
+        let count = |bank: &[PrgFlags], flag| bank.iter().filter(|x| x.contains(flag)).count();
+        let coverage = chunks(&self.prg, size)?.map(|(bank, flags)| PrgCoverage {
+            bank,
+            bytes: flags.len(),
+            code: count(flags, PrgFlags::CODE),
+            data: count(flags, PrgFlags::DATA),
+            logged: flags.iter().filter(|x| x.is_logged()).count(),
+        });
+        Ok(coverage.collect())
+    }
+
+    /// Coverage of each CHR-ROM bank of `size`, empty if there is no CHR-ROM.
+    ///
+    /// # Errors
+    ///
+    /// Return [`BankError`](../enum.BankError.html) if CHR-ROM size is not a multiple of
+    /// `size`.
+    pub fn chr_coverage(&self, size: BankSize) -> Result<Vec<ChrCoverage>, BankError> {
+        if self.chr.is_empty() {
+            return Ok(Vec::new());
+        }
+        let count = |bank: &[ChrFlags], flag| bank.iter().filter(|x| x.contains(flag)).count();
+        let coverage = chunks(&self.chr, size)?.map(|(bank, flags)| ChrCoverage {
+            bank,
+            bytes: flags.len(),
+            rendered: count(flags, ChrFlags::RENDERED),
+            read: count(flags, ChrFlags::READ),
+            logged: flags.iter().filter(|x| x.is_logged()).count(),
+        });
+        Ok(coverage.collect())
+    }
+}
+
+/// Split flags to banks like [`Banks`](../struct.Banks.html)
+fn chunks<T>(
+    flags: &[T], size: BankSize,
+) -> Result<impl Iterator<Item = (usize, &[T])>, BankError> {
+    if flags.is_empty() {
+        return Err(BankError::Empty);
+    }
+    if flags.len() % size.bytes() != 0 {
+        return Err(BankError::NotMultiple { rom_size: flags.len(), bank_size: size });
+    }
+    Ok(flags.chunks_exact(size.bytes()).enumerate())
+}
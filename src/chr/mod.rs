@@ -0,0 +1,195 @@
+//! Decode and encode CHR data, which is 8×8 tiles of 2 bits per pixel.
+//!
+//! Each tile is 16 bytes, two bit planes of 8 bytes. A byte is a row of 8 pixels, the most
+//! significant bit is the leftmost pixel. The first plane holds bit 0 of the color indices and
+//! the second plane holds bit 1.
+
+#[cfg(feature = "png")]
+mod png;
+mod sheet;
+
+pub use sheet::{pattern_tables, Sheet, PATTERN_TABLE_BYTES};
+
+use {
+    crate::{BankError, BankSize, NESFile},
+    std::{iter::FusedIterator, slice::ChunksExact},
+};
+
+/// Bytes of a tile in CHR data
+pub const TILE_BYTES: usize = 16;
+
+/// An 8×8 tile of color indices
+///
+/// # Examples
+///
+/// ```rust
+/// use dotnes::chr::Tile;
+///
+/// let mut bytes = [0; 16];
+/// bytes[0] = 0b1000_0001;
+/// bytes[8] = 0b1100_0000;
+/// let tile = Tile::decode(&bytes);
+/// assert_eq!(tile.pixels[0], [3, 2, 0, 0, 0, 0, 0, 1]);
+/// assert_eq!(tile.encode(), bytes);
+/// ```
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Tile {
+    /// Color indices in rows, from top to bottom, each row from left to right. Indices are
+    /// 0 to 3, higher bits are ignored by [`encode`](#method.encode)
+    pub pixels: [[u8; 8]; 8],
+}
+
+impl Tile {
+    /// Decode tile from its 16 bytes in CHR data.
+    #[must_use]
+    pub fn decode(bytes: &[u8; TILE_BYTES]) -> Self {
+        let mut pixels = [[0; 8]; 8];
+        for (y, row) in pixels.iter_mut().enumerate() {
+            let (low, high) = (bytes[y], bytes[y + 8]);
+            for (x, pixel) in row.iter_mut().enumerate() {
+                let shift = 7 - x;
+                *pixel = (low >> shift) & 1 | ((high >> shift) & 1) << 1;
+            }
+        }
+        Self { pixels }
+    }
+
+    /// Encode tile to its 16 bytes in CHR data.
+    #[must_use]
+    pub fn encode(&self) -> [u8; TILE_BYTES] {
+        let mut bytes = [0; TILE_BYTES];
+        for (y, row) in self.pixels.iter().enumerate() {
+            for (x, pixel) in row.iter().enumerate() {
+                let shift = 7 - x;
+                bytes[y] |= (pixel & 1) << shift;
+                bytes[y + 8] |= ((pixel >> 1) & 1) << shift;
+            }
+        }
+        bytes
+    }
+}
+
+/// Iterator over tiles of CHR data, created by [`tiles`](fn.tiles.html) or
+/// [`Bank::tiles`](../struct.Bank.html#method.tiles)
+#[derive(Debug, Clone)]
+pub struct Tiles<'a> {
+    inner: ChunksExact<'a, u8>,
+}
+
+impl Iterator for Tiles<'_> {
+    type Item = Tile;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(decode_chunk)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.inner.nth(n).map(decode_chunk)
+    }
+}
+
+impl DoubleEndedIterator for Tiles<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(decode_chunk)
+    }
+}
+
+impl ExactSizeIterator for Tiles<'_> {}
+
+impl FusedIterator for Tiles<'_> {}
+
+fn decode_chunk(chunk: &[u8]) -> Tile {
+    let mut bytes = [0; TILE_BYTES];
+    bytes.copy_from_slice(chunk);
+    Tile::decode(&bytes)
+}
+
+/// Iterate tiles of `chr`, trailing bytes which are not a whole tile are ignored.
+///
+/// # Examples
+///
+/// ```rust
+/// let chr = [0xFF; 16 * 4];
+/// assert_eq!(dotnes::chr::tiles(&chr).count(), 4);
+/// assert!(dotnes::chr::tiles(&chr).all(|tile| tile.pixels == [[3; 8]; 8]));
+/// ```
+#[must_use]
+pub fn tiles(chr: &[u8]) -> Tiles<'_> {
+    Tiles { inner: chr.chunks_exact(TILE_BYTES) }
+}
+
+/// Encode `tiles` to CHR data.
+pub fn encode<'a, I>(tiles: I) -> Vec<u8>
+where
+    I: IntoIterator<Item = &'a Tile>,
+{
+    tiles.into_iter().flat_map(Tile::encode).collect()
+}
+
+/// Replace tile number `index` of `chr` with `tile`, `None` if `chr` doesn't have that tile.
+///
+/// # Examples
+///
+/// ```rust
+/// use dotnes::chr::{self, Tile};
+///
+/// let mut chr = vec![0; 32];
+/// let tile = Tile { pixels: [[2; 8]; 8] };
+/// assert!(chr::write_tile(&mut chr, 1, &tile).is_some());
+/// assert_eq!(chr::tiles(&chr).nth(1), Some(tile));
+/// assert!(chr::write_tile(&mut chr, 2, &tile).is_none());
+/// ```
+pub fn write_tile(chr: &mut [u8], index: usize, tile: &Tile) -> Option<()> {
+    let start = index.checked_mul(TILE_BYTES)?;
+    chr.get_mut(start..start.checked_add(TILE_BYTES)?)?.copy_from_slice(&tile.encode());
+    Some(())
+}
+
+/// Replace CHR-ROM bank `index` of `size` in `file` with `chr`, return the new NES file bytes.
+///
+/// `data` is the bytes `file` is parsed from, they are copied as is except the bank, so the
+/// header is kept byte for byte.
+///
+/// # Examples
+///
+/// ```rust
+/// use dotnes::{header::Header, BankSize};
+///
+/// let mut data = Header::new(0, 0x4000, 0x2000).to_bytes().to_vec();
+/// data.resize(16 + 0x6000, 0);
+/// let nes = dotnes::parse(&data).unwrap();
+///
+/// let data = dotnes::chr::replace_bank(&data, &nes, BankSize::Size4K, 1, &[0xFF; 0x1000]).unwrap();
+/// let nes = dotnes::parse(&data).unwrap();
+/// assert_eq!(nes.chr_rom[0xFFF..0x1001], [0, 0xFF]);
+/// ```
+///
+/// # Errors
+///
+/// When `chr` is not `size`, CHR-ROM is not a multiple of `size` or doesn't have bank `index`,
+/// return Err([`BankError`](../enum.BankError.html)). When `data` is not the length of `file`,
+/// like a headerless image, return
+/// Err([`BankError::FileMismatch`](../enum.BankError.html#variant.FileMismatch)).
+pub fn replace_bank(
+    data: &[u8], file: &NESFile<'_>, size: BankSize, index: usize, chr: &[u8],
+) -> Result<Vec<u8>, BankError> {
+    if data.len() != file.layout.file_len() {
+        return Err(BankError::FileMismatch { size: data.len(), file_len: file.layout.file_len() });
+    }
+    if chr.len() != size.bytes() {
+        return Err(BankError::WrongSize { size: chr.len(), bank_size: size });
+    }
+    let count = file.chr_banks(size)?.len();
+    if index >= count {
+        return Err(BankError::OutOfRange { index, count });
+    }
+
+    let mut bytes = data.to_vec();
+    let start = file.layout.chr_rom.start + index * size.bytes();
+    bytes[start..start + size.bytes()].copy_from_slice(chr);
+    Ok(bytes)
+}
@@ -0,0 +1,66 @@
+use {
+    super::Sheet,
+    crate::palette::Rgb,
+    std::{
+        convert::TryFrom,
+        io::{self, BufRead, Seek},
+    },
+};
+
+impl Sheet {
+    /// Write as an indexed PNG image, with `colors` as its palette.
+    ///
+    /// Available with the `png` feature.
+    ///
+    /// # Errors
+    ///
+    /// When writing to `writer` failed or the sheet is too large for PNG, return the error.
+    pub fn write_png<W: io::Write>(&self, colors: &[Rgb; 4], writer: W) -> io::Result<()> {
+        let size = |x| {
+            u32::try_from(x).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "too large"))
+        };
+        let mut encoder = png::Encoder::new(writer, size(self.width)?, size(self.height)?);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_palette(colors.concat());
+
+        let mut writer = encoder.write_header()?;
+        let pixels: Vec<_> = self.pixels.iter().map(|x| x & 3).collect();
+        writer.write_image_data(&pixels)?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    /// Read a PNG image, each pixel gets the index of the nearest color in `colors`. Indexed
+    /// images are matched by the RGB colors of their palette too, fully transparent pixels get
+    /// index 0.
+    ///
+    /// Available with the `png` feature.
+    ///
+    /// # Errors
+    ///
+    /// When reading failed or the PNG is invalid, return the error.
+    pub fn read_png<R: BufRead + Seek>(reader: R, colors: &[Rgb; 4]) -> io::Result<Self> {
+        let mut decoder = png::Decoder::new(reader);
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder.read_info()?;
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "PNG is too large");
+        let mut buffer = vec![0; reader.output_buffer_size().ok_or_else(invalid)?];
+        let frame = reader.next_frame(&mut buffer)?;
+        let width = usize::try_from(frame.width).map_err(|_| invalid())?;
+        let height = usize::try_from(frame.height).map_err(|_| invalid())?;
+
+        let samples = frame.color_type.samples();
+        let rgb: Vec<u8> = buffer
+            .chunks_exact(frame.line_size)
+            .flat_map(|line| line[..width * samples].chunks_exact(samples))
+            .flat_map(|pixel| match *pixel {
+                [_, 0] | [_, _, _, 0] => colors[0],
+                [gray] | [gray, _] => [gray; 3],
+                [red, green, blue, ..] => [red, green, blue],
+                _ => unreachable!("8-bit PNG samples are 1 to 4 bytes"),
+            })
+            .collect();
+        Ok(Self::from_rgb(width, height, &rgb, colors))
+    }
+}
@@ -0,0 +1,142 @@
+use {
+    super::{encode, tiles, Tile},
+    crate::palette::Rgb,
+};
+
+/// Bytes of a pattern table, which is 256 tiles
+pub const PATTERN_TABLE_BYTES: usize = 0x1000;
+
+/// Tiles laid out as an image of color indices
+///
+/// # Examples
+///
+/// ```rust
+/// use dotnes::{chr::Sheet, palette::Palette};
+///
+/// let sheet = Sheet::pattern_table(&[0xFF; 0x1000]);
+/// assert_eq!((sheet.width, sheet.height), (128, 128));
+///
+/// let rgb = sheet.to_rgb(&Palette::NTSC.select([0x0F, 0x00, 0x10, 0x30]));
+/// assert_eq!(rgb.len(), 128 * 128 * 3);
+/// assert_eq!(rgb[..3], [236, 238, 236]);
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Sheet {
+    /// Width in pixels
+    pub width: usize,
+    /// Height in pixels
+    pub height: usize,
+    /// Color indices of pixels, in rows from top to bottom
+    pub pixels: Vec<u8>,
+}
+
+impl Sheet {
+    /// Lay out `tiles` from left to right, top to bottom, `columns` tiles a row. Space after the
+    /// last tile is filled with color 0.
+    ///
+    /// # Panics
+    ///
+    /// When `columns` is 0.
+    pub fn new<I>(tiles: I, columns: usize) -> Self
+    where
+        I: IntoIterator<Item = Tile>,
+    {
+        assert!(columns > 0, "sheet must have at least one column");
+        let tiles: Vec<_> = tiles.into_iter().collect();
+        let width = columns * 8;
+        let height = tiles.len().div_ceil(columns) * 8;
+        let mut pixels = vec![0; width * height];
+        for (i, tile) in tiles.iter().enumerate() {
+            let (left, top) = (i % columns * 8, i / columns * 8);
+            for (y, row) in tile.pixels.iter().enumerate() {
+                let start = (top + y) * width + left;
+                pixels[start..start + 8].copy_from_slice(row);
+            }
+        }
+        Self { width, height, pixels }
+    }
+
+    /// Lay out a pattern table in the usual 16 tiles a row, which is a 128×128 image for a
+    /// whole 4 KiB table.
+    #[must_use]
+    pub fn pattern_table(chr: &[u8]) -> Self {
+        Self::new(tiles(chr), 16)
+    }
+
+    /// Create from RGB bytes, each pixel gets the index of the nearest color in `colors`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dotnes::chr::Sheet;
+    ///
+    /// let colors = [[0, 0, 0], [255, 0, 0], [0, 255, 0], [255, 255, 255]];
+    /// let sheet = Sheet::from_rgb(2, 1, &[250, 10, 0, 200, 200, 200], &colors);
+    /// assert_eq!(sheet.pixels, [1, 3]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// When size of `rgb` is not `width * height * 3`.
+    #[must_use]
+    pub fn from_rgb(width: usize, height: usize, rgb: &[u8], colors: &[Rgb; 4]) -> Self {
+        assert_eq!(rgb.len(), width * height * 3, "RGB data size doesn't match sheet size");
+        let pixels = rgb.chunks_exact(3).map(|pixel| nearest(pixel, colors)).collect();
+        Self { width, height, pixels }
+    }
+
+    /// Split into tiles, from left to right, top to bottom. Pixels at right and bottom edge which
+    /// are not a whole tile are ignored.
+    #[must_use]
+    pub fn tiles(&self) -> Vec<Tile> {
+        let (columns, rows) = (self.width / 8, self.height / 8);
+        let mut tiles = vec![Tile::default(); columns * rows];
+        for (i, tile) in tiles.iter_mut().enumerate() {
+            let (left, top) = (i % columns * 8, i / columns * 8);
+            for (y, row) in tile.pixels.iter_mut().enumerate() {
+                let start = (top + y) * self.width + left;
+                row.copy_from_slice(&self.pixels[start..start + 8]);
+            }
+        }
+        tiles
+    }
+
+    /// Encode [`tiles`](#method.tiles) to CHR data.
+    #[must_use]
+    pub fn to_chr(&self) -> Vec<u8> {
+        encode(&self.tiles())
+    }
+
+    /// Convert to RGB bytes, by colors of the 4 indices.
+    #[must_use]
+    pub fn to_rgb(&self, colors: &[Rgb; 4]) -> Vec<u8> {
+        self.pixels.iter().flat_map(|&x| colors[usize::from(x & 3)]).collect()
+    }
+}
+
+/// Index of the color in `colors` nearest to `pixel`, by squared distance in RGB space
+fn nearest(pixel: &[u8], colors: &[Rgb; 4]) -> u8 {
+    let distance = |color: &Rgb| -> u32 {
+        pixel.iter().zip(color).map(|(&a, &b)| u32::from(a.abs_diff(b)).pow(2)).sum()
+    };
+    let mut best = 0;
+    for (i, color) in (0..).zip(colors) {
+        if distance(color) < distance(&colors[usize::from(best)]) {
+            best = i;
+        }
+    }
+    best
+}
+
+/// Lay out each 4 KiB of `chr` as a pattern table.
+///
+/// # Examples
+///
+/// ```rust
+/// let sheets = dotnes::chr::pattern_tables(&[0; 0x2000]);
+/// assert_eq!(sheets.len(), 2);
+/// ```
+#[must_use]
+pub fn pattern_tables(chr: &[u8]) -> Vec<Sheet> {
+    chr.chunks(PATTERN_TABLE_BYTES).map(Sheet::pattern_table).collect()
+}
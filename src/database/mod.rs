@@ -0,0 +1,405 @@
+//! ROM databases, to find correct header for headerless ROM data
+//!
+//! Two formats are supported: the NES 2.0 XML database (`nes20db.xml`), which has full header
+//! info, and Logiqx XML DAT files like No-Intro's, which only has header info when the DAT
+//! includes a `header` attribute.
+
+mod name;
+mod xml;
+
+pub use name::GameName;
+
+use {
+    super::{
+        checksum::{self, crc32, Crc32, Sha1},
+        header::{
+            parser::parse_header, ConsoleType, ExpansionDevice, ExtendedConsoleType, Header,
+            Mirroring, Timing, VsHardwareType, VsInfo, VsPPUType,
+        },
+        parse_recover, NESFile,
+    },
+    num_traits::FromPrimitive,
+    std::{error::Error, fmt},
+    xml::{Tag, Token, Tokens},
+};
+
+/// A game in database
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Entry {
+    /// Game name, from the comment in NES 2.0 database or name attribute in DAT
+    pub name: Option<String>,
+    /// Header of the game, `None` if database has no header info for it
+    pub header: Option<Header>,
+    /// CRC-32 of PRG-ROM
+    pub prg_rom_crc32: Option<u32>,
+    /// CRC-32 of CHR-ROM, `None` when the game uses CHR-RAM or database doesn't have it
+    pub chr_rom_crc32: Option<u32>,
+    /// CRC-32 of all ROM data without header, which is PRG-ROM followed by CHR-ROM, or of the
+    /// whole file for headered DATs
+    pub rom_crc32: Option<u32>,
+    /// SHA-1 of the same data as `rom_crc32`, preferred to CRC-32 when present
+    pub rom_sha1: Option<[u8; 20]>,
+}
+
+impl Entry {
+    const fn new() -> Self {
+        Self {
+            name: None,
+            header: None,
+            prg_rom_crc32: None,
+            chr_rom_crc32: None,
+            rom_crc32: None,
+            rom_sha1: None,
+        }
+    }
+
+    /// Split name to title, region, revision and other tags, see
+    /// [`GameName`](struct.GameName.html).
+    #[must_use]
+    pub fn game_name(&self) -> Option<GameName> {
+        self.name.as_deref().map(GameName::parse)
+    }
+
+    fn matches(&self, hashes: &Hashes) -> bool {
+        let by_parts =
+            self.prg_rom_crc32 == Some(hashes.prg_rom) && self.chr_rom_crc32 == hashes.chr_rom;
+        by_parts || self.matches_rom(hashes.rom_crc32, &hashes.rom_sha1)
+    }
+
+    fn matches_rom(&self, crc32: u32, sha1: &[u8; 20]) -> bool {
+        self.rom_sha1.map_or_else(|| self.rom_crc32 == Some(crc32), |x| x == *sha1)
+    }
+}
+
+/// Hashes of ROM data being looked up
+struct Hashes {
+    prg_rom: u32,
+    chr_rom: Option<u32>,
+    rom_crc32: u32,
+    rom_sha1: [u8; 20],
+}
+
+impl Hashes {
+    /// All ROM data is `sections` joined, which should include PRG-ROM and CHR-ROM
+    fn new(prg_rom: &[u8], chr_rom: &[u8], sections: &[&[u8]]) -> Self {
+        let mut crc = Crc32::new();
+        let mut sha1 = Sha1::new();
+        for section in sections {
+            crc.update(section);
+            sha1.update(section);
+        }
+        Self {
+            prg_rom: crc32(prg_rom),
+            chr_rom: if chr_rom.is_empty() { None } else { Some(crc32(chr_rom)) },
+            rom_crc32: crc.finish(),
+            rom_sha1: sha1.finish(),
+        }
+    }
+}
+
+/// Load database failed reason
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum DatabaseError {
+    /// XML syntax is invalid
+    Syntax {
+        /// Byte offset where the error is found
+        offset: usize,
+    },
+    /// Value of an attribute can't be parsed
+    InvalidValue {
+        /// Attribute name
+        attribute: &'static str,
+        /// The invalid value
+        value: String,
+    },
+}
+
+impl fmt::Display for DatabaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax { offset } => write!(f, "invalid XML syntax at byte {offset}"),
+            Self::InvalidValue { attribute, value } => {
+                write!(f, "invalid value {value:?} of attribute {attribute}")
+            }
+        }
+    }
+}
+
+impl Error for DatabaseError {}
+
+/// Games loaded from database files
+///
+/// # Examples
+///
+/// ```rust
+/// use dotnes::database::Database;
+///
+/// let database = Database::from_nes20db(
+///     r#"<nes20db>
+///     <game>
+///         <!-- Branch Basics -->
+///         <prgrom size="16384" crc32="654EC82D"/>
+///         <chrram size="8192"/>
+///         <pcb mapper="0" submapper="0" mirroring="H" battery="0"/>
+///         <console type="0" region="0"/>
+///     </game>
+///     </nes20db>"#,
+/// )
+/// .unwrap();
+///
+/// let data = std::fs::read("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+/// let prg_rom = &data[16..16 + 16384];
+/// let bytes = database.assemble(prg_rom, &[]).unwrap();
+/// let nes = dotnes::parse(&bytes).unwrap();
+/// assert_eq!(nes.header.chr_ram_size, 8192);
+/// assert_eq!(nes.prg_rom, prg_rom);
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Database {
+    /// All games, in the order of files
+    pub entries: Vec<Entry>,
+}
+
+impl Database {
+    /// Load NES 2.0 XML database, which is the `nes20db.xml` file.
+    ///
+    /// # Errors
+    ///
+    /// When XML syntax is invalid or a number can't be parsed, return
+    /// Err([`DatabaseError`](enum.DatabaseError.html)).
+    pub fn from_nes20db(xml: &str) -> Result<Self, DatabaseError> {
+        let mut entries = Vec::new();
+        let mut game: Option<(Entry, Header)> = None;
+
+        for token in Tokens::new(xml) {
+            match (token.map_err(|offset| DatabaseError::Syntax { offset })?, &mut game) {
+                (Token::Start(tag), None) if tag.name == "game" => {
+                    let mut header = Header::new(0, 0, 0);
+                    header.chr_ram_size = 0;
+                    game = Some((Entry::new(), header));
+                }
+                (Token::Comment(comment), Some((entry, _))) if entry.name.is_none() => {
+                    entry.name = Some(comment.trim().to_owned());
+                }
+                (Token::Start(tag), Some((entry, header))) => nes20db_element(&tag, entry, header)?,
+                (Token::End("game"), game @ Some(_)) => {
+                    if let Some((mut entry, header)) = game.take() {
+                        entry.header = Some(header);
+                        entries.push(entry);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Load Logiqx XML DAT file, like No-Intro's.
+    ///
+    /// Each `<rom>` with a `crc` attribute becomes an entry, its CRC-32 and SHA-1 are of
+    /// headerless ROM data for headerless DATs and DATs using a header skipper list, or of the
+    /// whole file for headered DATs, see [`identify_bytes`](#method.identify_bytes). Header is
+    /// read from the `header` attribute if present, which is the 16 header bytes in hex.
+    ///
+    /// # Errors
+    ///
+    /// When XML syntax is invalid or an attribute can't be parsed, return
+    /// Err([`DatabaseError`](enum.DatabaseError.html)).
+    pub fn from_dat(xml: &str) -> Result<Self, DatabaseError> {
+        let mut entries = Vec::new();
+        let mut name = None;
+
+        for token in Tokens::new(xml) {
+            match token.map_err(|offset| DatabaseError::Syntax { offset })? {
+                Token::Start(tag) if tag.name == "game" || tag.name == "machine" => {
+                    name = tag.attribute("name").map(str::to_owned);
+                }
+                Token::Start(tag) if tag.name == "rom" => {
+                    if let Some(crc) = hex(&tag, "crc")? {
+                        let mut entry = Entry::new();
+                        entry.name.clone_from(&name);
+                        entry.rom_crc32 = Some(crc);
+                        entry.rom_sha1 = sha1(&tag)?;
+                        entry.header = dat_header(&tag)?;
+                        entries.push(entry);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Find the game of ROM data by CRC-32.
+    ///
+    /// Matches by CRC-32 of PRG-ROM and CHR-ROM first, then CRC-32 of all ROM data.
+    #[must_use]
+    pub fn find(&self, prg_rom: &[u8], chr_rom: &[u8]) -> Option<&Entry> {
+        let hashes = Hashes::new(prg_rom, chr_rom, &[prg_rom, chr_rom]);
+        self.entries.iter().find(|x| x.matches(&hashes))
+    }
+
+    /// Find the game of a parsed NES file by hashes of its headerless data.
+    ///
+    /// Headerless data is trainer, PRG-ROM, CHR-ROM and miscellaneous ROMs declared by header,
+    /// trailing bytes are excluded. The header itself is not used, so files with wrong header
+    /// are still found.
+    #[must_use]
+    pub fn identify(&self, file: &NESFile<'_>) -> Option<&Entry> {
+        let hashes = Hashes::new(file.prg_rom, file.chr_rom, &file.headerless());
+        self.entries.iter().find(|x| x.matches(&hashes))
+    }
+
+    /// Find the game of NES file bytes, by hashes of headerless data like
+    /// [`identify`](#method.identify), then by hashes of the whole file as in headered DATs.
+    ///
+    /// Truncated or overdumped files are accepted, see
+    /// [`parse_recover`](../fn.parse_recover.html).
+    #[must_use]
+    pub fn identify_bytes(&self, data: &[u8]) -> Option<&Entry> {
+        let headerless = parse_recover(data).ok().and_then(|x| self.identify(&x.file));
+        headerless.or_else(|| {
+            let (crc32, sha1) = (crc32(data), checksum::sha1(data));
+            self.entries.iter().find(|x| x.matches_rom(crc32, &sha1))
+        })
+    }
+
+    /// Build NES file bytes from ROM data with header found in database, `None` if not found.
+    ///
+    /// Trainer and miscellaneous ROMs are not included, so header flags of them are cleared.
+    /// Parse the result by [`parse`](../fn.parse.html).
+    #[must_use]
+    pub fn assemble(&self, prg_rom: &[u8], chr_rom: &[u8]) -> Option<Vec<u8>> {
+        let hashes = Hashes::new(prg_rom, chr_rom, &[prg_rom, chr_rom]);
+        let mut header =
+            self.entries.iter().filter(|x| x.matches(&hashes)).find_map(|x| x.header.clone())?;
+        header.has_trainer = false;
+        header.miscellaneous_rom_count = 0;
+
+        let mut file = Vec::with_capacity(16 + prg_rom.len() + chr_rom.len());
+        file.extend_from_slice(&header.to_bytes());
+        file.extend_from_slice(prg_rom);
+        file.extend_from_slice(chr_rom);
+        Some(file)
+    }
+}
+
+fn invalid(attribute: &'static str, value: &str) -> DatabaseError {
+    DatabaseError::InvalidValue { attribute, value: value.to_owned() }
+}
+
+fn number(tag: &Tag<'_>, attribute: &'static str) -> Result<Option<u32>, DatabaseError> {
+    tag.attribute(attribute)
+        .map(|value| value.trim().parse().map_err(|_| invalid(attribute, value)))
+        .transpose()
+}
+
+fn hex(tag: &Tag<'_>, attribute: &'static str) -> Result<Option<u32>, DatabaseError> {
+    tag.attribute(attribute)
+        .map(|value| u32::from_str_radix(value.trim(), 16).map_err(|_| invalid(attribute, value)))
+        .transpose()
+}
+
+fn sha1(tag: &Tag<'_>) -> Result<Option<[u8; 20]>, DatabaseError> {
+    let Some(value) = tag.attribute("sha1") else {
+        return Ok(None);
+    };
+    let value = value.trim();
+    let mut digest = [0; 20];
+    if value.len() != 40 || !value.is_ascii() {
+        return Err(invalid("sha1", value));
+    }
+    for (byte, i) in digest.iter_mut().zip((0..40).step_by(2)) {
+        *byte = u8::from_str_radix(&value[i..i + 2], 16).map_err(|_| invalid("sha1", value))?;
+    }
+    Ok(Some(digest))
+}
+
+/// Parse a small number attribute which must fit `T`
+fn small<T: FromPrimitive>(
+    tag: &Tag<'_>, attribute: &'static str,
+) -> Result<Option<T>, DatabaseError> {
+    number(tag, attribute)?
+        .map(|x| T::from_u32(x).ok_or_else(|| invalid(attribute, &x.to_string())))
+        .transpose()
+}
+
+fn nes20db_element(
+    tag: &Tag<'_>, entry: &mut Entry, header: &mut Header,
+) -> Result<(), DatabaseError> {
+    let size = number(tag, "size")?.unwrap_or(0);
+    match tag.name {
+        "prgrom" => {
+            header.prg_rom_size = size;
+            entry.prg_rom_crc32 = hex(tag, "crc32")?;
+        }
+        "chrrom" => {
+            header.chr_rom_size = size;
+            entry.chr_rom_crc32 = hex(tag, "crc32")?;
+        }
+        "rom" => {
+            entry.rom_crc32 = hex(tag, "crc32")?;
+            entry.rom_sha1 = sha1(tag)?;
+        }
+        "prgram" => header.prg_ram_size = size,
+        "prgnvram" => header.prg_nvram_size = size,
+        "chrram" => header.chr_ram_size = size,
+        "chrnvram" => header.chr_nvram_size = size,
+        "trainer" => header.has_trainer = size > 0,
+        "miscrom" => header.miscellaneous_rom_count = small(tag, "number")?.unwrap_or(0),
+        "pcb" => {
+            header.mapper = small(tag, "mapper")?.unwrap_or(0);
+            header.sub_mapper = small(tag, "submapper")?.unwrap_or(0);
+            header.has_persistent_memory = number(tag, "battery")?.unwrap_or(0) != 0;
+            match tag.attribute("mirroring") {
+                Some("V") => header.mirroring = Mirroring::Vertical,
+                Some("4") => header.is_four_screen = true,
+                _ => {}
+            }
+        }
+        "console" | "vs" => console_element(tag, header)?,
+        "expansion" => {
+            header.default_expansion_device =
+                small(tag, "type")?.unwrap_or(ExpansionDevice::Unspecified);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn dat_header(tag: &Tag<'_>) -> Result<Option<Header>, DatabaseError> {
+    let Some(value) = tag.attribute("header") else {
+        return Ok(None);
+    };
+    let error = || invalid("header", value);
+
+    let bytes = value
+        .split_whitespace()
+        .map(|x| u8::from_str_radix(x, 16))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| error())?;
+    if bytes.len() != 16 {
+        return Err(error());
+    }
+    parse_header(&bytes).map(Some).map_err(|_| error())
+}
+
+fn console_element(tag: &Tag<'_>, header: &mut Header) -> Result<(), DatabaseError> {
+    if tag.name == "console" {
+        header.timing = small(tag, "region")?.unwrap_or(Timing::NTSC);
+        header.console_type = match number(tag, "type")?.unwrap_or(0) {
+            0 => ConsoleType::NES,
+            1 => ConsoleType::Vs(VsInfo::default()),
+            2 => ConsoleType::PC10,
+            x => ConsoleType::Extend(
+                ExtendedConsoleType::from_u32(x).unwrap_or(ExtendedConsoleType::Reserved),
+            ),
+        };
+    } else if let ConsoleType::Vs(info) = &mut header.console_type {
+        info.hardware_type = small(tag, "hardware")?.unwrap_or(VsHardwareType::UniSystemNormal);
+        info.ppu_type = small(tag, "ppu")?.unwrap_or(VsPPUType::RP2C03B);
+    }
+    Ok(())
+}
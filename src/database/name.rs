@@ -0,0 +1,157 @@
+/// Region names used by No-Intro
+const REGIONS: &[&str] = &[
+    "World",
+    "USA",
+    "Europe",
+    "Japan",
+    "Asia",
+    "Australia",
+    "Brazil",
+    "Canada",
+    "China",
+    "France",
+    "Germany",
+    "Hong Kong",
+    "Italy",
+    "Korea",
+    "Netherlands",
+    "Russia",
+    "Scandinavia",
+    "Spain",
+    "Sweden",
+    "Taiwan",
+    "UK",
+];
+
+/// `GoodNES` region codes and the No-Intro names of them
+const REGION_CODES: &[(&str, &str)] = &[
+    ("A", "Australia"),
+    ("B", "Brazil"),
+    ("C", "China"),
+    ("E", "Europe"),
+    ("F", "France"),
+    ("G", "Germany"),
+    ("HK", "Hong Kong"),
+    ("I", "Italy"),
+    ("J", "Japan"),
+    ("K", "Korea"),
+    ("NL", "Netherlands"),
+    ("R", "Russia"),
+    ("S", "Spain"),
+    ("Sw", "Sweden"),
+    ("U", "USA"),
+    ("UK", "UK"),
+    ("W", "World"),
+];
+
+/// Game name split by No-Intro or `GoodNES` naming convention
+///
+/// # Examples
+///
+/// ```rust
+/// use dotnes::database::GameName;
+///
+/// let name = GameName::parse("Super Mario Bros. 3 (USA) (Rev 1)");
+/// assert_eq!(name.title, "Super Mario Bros. 3");
+/// assert_eq!(name.regions, ["USA"]);
+/// assert_eq!(name.revision.as_deref(), Some("Rev 1"));
+///
+/// let name = GameName::parse("Super Mario Bros. 3 (JU) (PRG1) [!]");
+/// assert_eq!(name.regions, ["Japan", "USA"]);
+/// assert_eq!(name.revision.as_deref(), Some("PRG1"));
+/// assert_eq!(name.flags, ["!"]);
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct GameName {
+    /// Title without any tags
+    pub title: String,
+    /// Regions in No-Intro spelling, like `USA` and `Japan`, `GoodNES` codes are converted
+    pub regions: Vec<String>,
+    /// Revision, like `Rev 1`, `v1.1` or `GoodNES` style `PRG1`
+    pub revision: Option<String>,
+    /// Other tags in parentheses, like `Beta` or `Unl`
+    pub tags: Vec<String>,
+    /// `GoodNES` dump flags in square brackets, like `!`, `b1` or `T+Eng`
+    pub flags: Vec<String>,
+}
+
+impl GameName {
+    /// Split `name` to title and tags.
+    #[must_use]
+    pub fn parse(name: &str) -> Self {
+        let title_end = name.find(['(', '[']).unwrap_or(name.len());
+        let mut result = Self { title: name[..title_end].trim().to_owned(), ..Self::default() };
+
+        let mut rest = &name[title_end..];
+        while let Some(start) = rest.find(['(', '[']) {
+            let close = if rest[start..].starts_with('(') { ')' } else { ']' };
+            let Some(length) = rest[start..].find(close) else {
+                break;
+            };
+            let group = rest[start + 1..start + length].trim();
+            rest = &rest[start + length + 1..];
+
+            if close == ']' {
+                result.flags.push(group.to_owned());
+            } else if let Some(regions) = regions(group).filter(|_| result.regions.is_empty()) {
+                result.regions = regions;
+            } else if is_revision(group) && result.revision.is_none() {
+                result.revision = Some(group.to_owned());
+            } else {
+                result.tags.push(group.to_owned());
+            }
+        }
+
+        result
+    }
+
+    /// If the name marks a hack, by `GoodNES` flag `[h]` or tag `(Hack)`.
+    #[must_use]
+    pub fn is_hack(&self) -> bool {
+        self.flags.iter().any(|x| is_flag(x, "h")) || self.tags.iter().any(|x| x == "Hack")
+    }
+
+    /// If the name marks a fan translation, by `GoodNES` flag `[T+Eng]`, `[T-Eng]` or a tag
+    /// starts with `Translated`.
+    #[must_use]
+    pub fn is_translation(&self) -> bool {
+        self.flags.iter().any(|x| x.starts_with("T+") || x.starts_with("T-"))
+            || self.tags.iter().any(|x| x.starts_with("Translated"))
+    }
+
+    /// If the name marks a bad dump, by `GoodNES` flag `[b]` or No-Intro flag `[b]`.
+    #[must_use]
+    pub fn is_bad_dump(&self) -> bool {
+        self.flags.iter().any(|x| is_flag(x, "b"))
+    }
+}
+
+/// If `flag` is `code` followed by optional number or uppercase details, like `h1` and `hM04`
+fn is_flag(flag: &str, code: &str) -> bool {
+    flag.strip_prefix(code).is_some_and(|rest| !rest.starts_with(|c: char| c.is_ascii_lowercase()))
+}
+
+fn regions(group: &str) -> Option<Vec<String>> {
+    if group.is_empty() {
+        return None;
+    }
+    let no_intro: Option<Vec<_>> = group
+        .split(',')
+        .map(|x| REGIONS.iter().find(|&&region| region == x.trim()).map(|x| (*x).to_owned()))
+        .collect();
+    no_intro.or_else(|| {
+        let code = |code: &str| {
+            REGION_CODES.iter().find(|(x, _)| *x == code).map(|(_, name)| (*name).to_owned())
+        };
+        code(group).map(|x| vec![x]).or_else(|| {
+            group.chars().map(|c| code(c.encode_utf8(&mut [0; 4]))).collect::<Option<Vec<_>>>()
+        })
+    })
+}
+
+fn is_revision(group: &str) -> bool {
+    let digits_after = |prefix: &str| {
+        group.strip_prefix(prefix).is_some_and(|x| x.starts_with(|c: char| c.is_ascii_digit()))
+    };
+    group.starts_with("Rev ") || digits_after("v") || digits_after("V") || digits_after("PRG")
+}
@@ -0,0 +1,179 @@
+//! Minimal XML tokenizer, only enough for ROM database files.
+//!
+//! Namespaces, CDATA and DTD internal subsets are not supported.
+
+/// A piece of XML document
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Token<'a> {
+    /// Start tag, or an empty element tag
+    Start(Tag<'a>),
+    /// End tag with element name
+    End(&'a str),
+    /// Comment content, without `<!--` and `-->`
+    Comment(&'a str),
+    /// Text between tags, entities are decoded
+    Text(String),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Tag<'a> {
+    pub name: &'a str,
+    pub attributes: Vec<(&'a str, String)>,
+    /// If the tag is `<name ... />`, which has no content and end tag
+    pub empty: bool,
+}
+
+impl Tag<'_> {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(x, _)| *x == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// Iterate tokens of XML document, error is byte offset where syntax is invalid
+#[derive(Debug)]
+pub struct Tokens<'a> {
+    input: &'a str,
+    offset: usize,
+}
+
+impl<'a> Tokens<'a> {
+    pub const fn new(input: &'a str) -> Self {
+        Self { input, offset: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.offset..]
+    }
+
+    /// Skip to after `pattern`, return content before it
+    fn until(&mut self, pattern: &str) -> Result<&'a str, usize> {
+        let rest = self.rest();
+        let end = rest.find(pattern).ok_or(self.offset)?;
+        self.offset += end + pattern.len();
+        Ok(&rest[..end])
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.offset += rest.len() - rest.trim_start().len();
+    }
+
+    fn name(&mut self) -> Result<&'a str, usize> {
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '='))
+            .unwrap_or(rest.len());
+        if end == 0 {
+            return Err(self.offset);
+        }
+        self.offset += end;
+        Ok(&rest[..end])
+    }
+
+    fn tag(&mut self) -> Result<Tag<'a>, usize> {
+        let name = self.name()?;
+        let mut attributes = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.offset += 2;
+                return Ok(Tag { name, attributes, empty: true });
+            }
+            if rest.starts_with('>') {
+                self.offset += 1;
+                return Ok(Tag { name, attributes, empty: false });
+            }
+
+            let key = self.name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(self.offset);
+            }
+            self.offset += 1;
+            self.skip_whitespace();
+            let Some(quote @ ('"' | '\'')) = self.rest().chars().next() else {
+                return Err(self.offset);
+            };
+            self.offset += 1;
+            let start = self.offset;
+            let value = self.until(if quote == '"' { "\"" } else { "'" })?;
+            attributes.push((key, unescape(value).ok_or(start)?));
+        }
+    }
+
+    fn token(&mut self) -> Result<Option<Token<'a>>, usize> {
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                return Ok(None);
+            }
+            if rest.starts_with("<!--") {
+                self.offset += 4;
+                return self.until("-->").map(|x| Some(Token::Comment(x)));
+            }
+            if rest.starts_with("<?") {
+                self.until("?>")?;
+            } else if rest.starts_with("<!") {
+                self.until(">")?;
+            } else if rest.starts_with("</") {
+                self.offset += 2;
+                let name = self.name()?;
+                self.skip_whitespace();
+                self.until(">")?;
+                return Ok(Some(Token::End(name)));
+            } else if rest.starts_with('<') {
+                self.offset += 1;
+                return self.tag().map(|x| Some(Token::Start(x)));
+            } else {
+                let start = self.offset;
+                let end = rest.find('<').unwrap_or(rest.len());
+                self.offset += end;
+                return unescape(&rest[..end]).map(|x| Some(Token::Text(x))).ok_or(start);
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Result<Token<'a>, usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.token();
+        if result.is_err() {
+            // stop after error
+            self.offset = self.input.len();
+        }
+        result.transpose()
+    }
+}
+
+/// Decode predefined and numeric entities, `None` if there is unknown entity
+fn unescape(text: &str) -> Option<String> {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+        let end = rest.find(';')?;
+        let c = match &rest[..end] {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            entity => {
+                let code = entity.strip_prefix('#')?;
+                let code = match code.strip_prefix('x') {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => code.parse().ok()?,
+                };
+                std::char::from_u32(code)?
+            }
+        };
+        result.push(c);
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    Some(result)
+}
@@ -0,0 +1,244 @@
+//! Disassemble 6502 machine code in PRG-ROM.
+//!
+//! Official opcodes and unofficial ones of the NMOS 6502 are decoded. Text output is ca65 syntax,
+//! unofficial opcodes are written as `.byte` with the instruction in comment, so the output
+//! assembles back to the same bytes without `.setcpu "6502X"`.
+//!
+//! [`trace`](fn.trace.html) follows control flow from the vectors to tell code from data, for a
+//! labelled disassembly.
+
+mod opcode;
+mod trace;
+
+pub use {
+    opcode::{AddressingMode, Opcode},
+    trace::{trace, trace_from, ByteKind, Trace},
+};
+
+use {
+    crate::{
+        mapper::{BankState, MemoryMap},
+        NESFile,
+    },
+    std::{fmt, ops::Range},
+};
+
+/// A decoded instruction
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Instruction {
+    /// CPU address of the opcode byte
+    pub address: u16,
+    /// The opcode
+    pub opcode: Opcode,
+    /// Operand value, the offset byte for relative mode, 0 if there is no operand
+    pub operand: u16,
+}
+
+impl Instruction {
+    /// Decode the instruction at start of `bytes`, `None` if `bytes` is shorter than the
+    /// instruction.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dotnes::disasm::Instruction;
+    ///
+    /// let instruction = Instruction::decode(&[0xD0, 0xFE], 0xC000).unwrap();
+    /// assert_eq!(instruction.target(), Some(0xC000));
+    /// assert_eq!(instruction.to_string(), "bne $C000");
+    ///
+    /// let instruction = Instruction::decode(&[0xAD, 0x10, 0x00], 0xC000).unwrap();
+    /// assert_eq!(instruction.to_string(), "lda a:$0010");
+    /// assert!(Instruction::decode(&[0xAD, 0x10], 0xC000).is_none());
+    /// ```
+    #[must_use]
+    pub fn decode(bytes: &[u8], address: u16) -> Option<Self> {
+        let opcode = Opcode::decode(*bytes.first()?);
+        let operand = match *bytes.get(1..=opcode.mode.operand_bytes())? {
+            [low, high] => u16::from_le_bytes([low, high]),
+            [value] => u16::from(value),
+            _ => 0,
+        };
+        Some(Self { address, opcode, operand })
+    }
+
+    /// Bytes of the instruction, including the opcode byte.
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        1 + self.opcode.mode.operand_bytes()
+    }
+
+    /// Encode back to bytes.
+    #[must_use]
+    pub fn bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.opcode.byte];
+        bytes.extend_from_slice(&self.operand.to_le_bytes()[..self.opcode.mode.operand_bytes()]);
+        bytes
+    }
+
+    /// Address of the next instruction in memory.
+    #[must_use]
+    pub const fn next_address(&self) -> u16 {
+        #[allow(clippy::cast_possible_truncation)] // size is at most 3
+        let size = self.size() as u16;
+        self.address.wrapping_add(size)
+    }
+
+    /// Address this instruction may jump to: branch target, or the address of `jmp` and `jsr`
+    /// with absolute operand. `None` for other instructions, including `jmp` indirect.
+    #[must_use]
+    pub fn target(&self) -> Option<u16> {
+        match (self.opcode.mode, self.opcode.byte) {
+            (AddressingMode::Relative, _) => {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+                // signed byte
+                let offset = i16::from(self.operand as u8 as i8);
+                Some(self.next_address().wrapping_add_signed(offset))
+            }
+            (_, 0x4C | 0x20) => Some(self.operand),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    /// Write in ca65 syntax, official opcodes only, see module document for unofficial ones
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mnemonic = self.opcode.mnemonic.to_ascii_lowercase();
+        let operand = self.operand;
+        // ca65 would use zero page mode for small absolute addresses, `a:` forces absolute
+        let absolute = if operand < 0x100 { "a:" } else { "" };
+        match self.opcode.mode {
+            AddressingMode::Implied => write!(f, "{mnemonic}"),
+            AddressingMode::Accumulator => write!(f, "{mnemonic} a"),
+            AddressingMode::Immediate => write!(f, "{mnemonic} #${operand:02X}"),
+            AddressingMode::ZeroPage => write!(f, "{mnemonic} ${operand:02X}"),
+            AddressingMode::ZeroPageX => write!(f, "{mnemonic} ${operand:02X},x"),
+            AddressingMode::ZeroPageY => write!(f, "{mnemonic} ${operand:02X},y"),
+            AddressingMode::Absolute => write!(f, "{mnemonic} {absolute}${operand:04X}"),
+            AddressingMode::AbsoluteX => write!(f, "{mnemonic} {absolute}${operand:04X},x"),
+            AddressingMode::AbsoluteY => write!(f, "{mnemonic} {absolute}${operand:04X},y"),
+            AddressingMode::Indirect => write!(f, "{mnemonic} (${operand:04X})"),
+            AddressingMode::IndirectX => write!(f, "{mnemonic} (${operand:02X},x)"),
+            AddressingMode::IndirectY => write!(f, "{mnemonic} (${operand:02X}),y"),
+            AddressingMode::Relative => {
+                write!(f, "{mnemonic} ${:04X}", self.target().unwrap_or_default())
+            }
+        }
+    }
+}
+
+/// Iterator over instructions, created by [`disassemble`](fn.disassemble.html)
+#[derive(Debug, Clone)]
+pub struct Instructions<'a> {
+    data: &'a [u8],
+    address: u16,
+}
+
+impl<'a> Instructions<'a> {
+    /// Bytes not disassembled yet, after the iteration ends they are an incomplete instruction.
+    #[must_use]
+    pub const fn rest(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// CPU address of [`rest`](#method.rest).
+    #[must_use]
+    pub const fn address(&self) -> u16 {
+        self.address
+    }
+}
+
+impl Iterator for Instructions<'_> {
+    type Item = Instruction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let instruction = Instruction::decode(self.data, self.address)?;
+        self.data = &self.data[instruction.size()..];
+        self.address = instruction.next_address();
+        Some(instruction)
+    }
+}
+
+/// Disassemble `data` linearly, the first byte is at CPU `address`.
+///
+/// # Examples
+///
+/// ```rust
+/// let instructions: Vec<_> = dotnes::disasm::disassemble(&[0x78, 0xD8, 0xA2, 0xFF], 0xC000)
+///     .map(|x| x.to_string())
+///     .collect();
+/// assert_eq!(instructions, ["sei", "cld", "ldx #$FF"]);
+/// ```
+#[must_use]
+pub const fn disassemble(data: &[u8], address: u16) -> Instructions<'_> {
+    Instructions { data, address }
+}
+
+/// CPU address of PRG-ROM `offset` in `file` at power on.
+///
+/// The mapper's power on bank state decides the address. When the offset is mirrored, like NROM
+/// with 16K PRG-ROM, the highest address is used. `None` if the mapper is not supported or the
+/// offset is not mapped at power on.
+///
+/// # Examples
+///
+/// ```rust
+/// let data = std::fs::read("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+/// let nes = dotnes::parse(&data).unwrap();
+/// assert_eq!(dotnes::disasm::cpu_address(&nes, 0), Some(0xC000));
+/// ```
+#[must_use]
+pub fn cpu_address(file: &NESFile<'_>, offset: usize) -> Option<u16> {
+    let state = BankState::power_on(file.header.mapper)?;
+    MemoryMap::for_file(file, &state).prg_rom_to_cpu(offset).into_iter().max()
+}
+
+/// Disassemble `range` of PRG-ROM in `file`, the base address is decided by
+/// [`cpu_address`](fn.cpu_address.html). `None` if the range is out of PRG-ROM or its start is
+/// not mapped at power on.
+#[must_use]
+pub fn disassemble_prg<'a>(file: &NESFile<'a>, range: Range<usize>) -> Option<Instructions<'a>> {
+    let address = cpu_address(file, range.start)?;
+    Some(disassemble(file.prg_rom.get(range)?, address))
+}
+
+/// Disassemble `data` to ca65 source, the first byte is at CPU `address`.
+///
+/// # Examples
+///
+/// ```rust
+/// let source = dotnes::disasm::to_ca65(&[0xA9, 0x00, 0xA7, 0x10, 0x4C], 0x8000);
+/// assert_eq!(
+///     source,
+///     ".org $8000\n\
+///      \x20   lda #$00 ; $8000\n\
+///      \x20   .byte $A7, $10 ; $8002 lax $10\n\
+///      \x20   .byte $4C ; $8004\n"
+/// );
+/// ```
+#[must_use]
+pub fn to_ca65(data: &[u8], address: u16) -> String {
+    let mut source = format!(".org ${address:04X}\n");
+    let mut instructions = disassemble(data, address);
+    for instruction in &mut instructions {
+        let address = instruction.address;
+        let line = if instruction.opcode.official {
+            format!("    {instruction} ; ${address:04X}\n")
+        } else {
+            let bytes = byte_list(&instruction.bytes());
+            format!("    .byte {bytes} ; ${address:04X} {instruction}\n")
+        };
+        source.push_str(&line);
+    }
+    if !instructions.rest().is_empty() {
+        let bytes = byte_list(instructions.rest());
+        let line = format!("    .byte {bytes} ; ${:04X}\n", instructions.address());
+        source.push_str(&line);
+    }
+    source
+}
+
+fn byte_list(bytes: &[u8]) -> String {
+    bytes.iter().map(|x| format!("${x:02X}")).collect::<Vec<_>>().join(", ")
+}
@@ -0,0 +1,213 @@
+/// How an instruction gets its operand
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum AddressingMode {
+    /// No operand, like `clc`
+    Implied,
+    /// The accumulator, like `asl a`
+    Accumulator,
+    /// A byte value, like `lda #$10`
+    Immediate,
+    /// Zero page address, like `lda $10`
+    ZeroPage,
+    /// Zero page address indexed by X, like `lda $10,x`
+    ZeroPageX,
+    /// Zero page address indexed by Y, like `ldx $10,y`
+    ZeroPageY,
+    /// Absolute address, like `lda $1234`
+    Absolute,
+    /// Absolute address indexed by X, like `lda $1234,x`
+    AbsoluteX,
+    /// Absolute address indexed by Y, like `lda $1234,y`
+    AbsoluteY,
+    /// Address read from an absolute address, only `jmp ($1234)`
+    Indirect,
+    /// Address read from zero page address indexed by X, like `lda ($10,x)`
+    IndirectX,
+    /// Address read from zero page address then indexed by Y, like `lda ($10),y`
+    IndirectY,
+    /// Signed offset from the next instruction, for branches
+    Relative,
+}
+
+impl AddressingMode {
+    /// Bytes of operand after the opcode byte
+    #[must_use]
+    pub const fn operand_bytes(self) -> usize {
+        match self {
+            Self::Implied | Self::Accumulator => 0,
+            Self::Absolute | Self::AbsoluteX | Self::AbsoluteY | Self::Indirect => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// What an opcode byte does
+///
+/// # Examples
+///
+/// ```rust
+/// use dotnes::disasm::{AddressingMode, Opcode};
+///
+/// let opcode = Opcode::decode(0xA9);
+/// assert_eq!(opcode.mnemonic, "LDA");
+/// assert_eq!(opcode.mode, AddressingMode::Immediate);
+/// assert!(opcode.official);
+/// assert!(!Opcode::decode(0xA7).official);
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Opcode {
+    /// The opcode byte
+    pub byte: u8,
+    /// Mnemonic in upper case, unofficial opcodes use the names of ca65, like `LAX` and `ISC`
+    pub mnemonic: &'static str,
+    /// Addressing mode
+    pub mode: AddressingMode,
+    /// If it's one of the 151 official opcodes
+    pub official: bool,
+}
+
+impl Opcode {
+    /// Decode an opcode byte, all 256 values are defined on NMOS 6502. Opcodes which halt the
+    /// CPU are `JAM`.
+    #[must_use]
+    pub const fn decode(byte: u8) -> Self {
+        let (mnemonic, mode) = OPCODES[byte as usize];
+        Self { byte, mnemonic, mode, official: is_official(byte, mnemonic) }
+    }
+}
+
+const fn is_official(byte: u8, mnemonic: &str) -> bool {
+    if byte == 0xEA {
+        return true;
+    }
+    if byte == 0xEB {
+        return false;
+    }
+    let mut i = 0;
+    while i < UNOFFICIAL.len() {
+        if eq(UNOFFICIAL[i].as_bytes(), mnemonic.as_bytes()) {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Mnemonics only used by unofficial opcodes, `NOP` except $EA and `SBC` $EB are unofficial too
+const UNOFFICIAL: [&str; 20] = [
+    "ALR", "ANC", "ANE", "ARR", "AXS", "DCP", "ISC", "JAM", "LAS", "LAX", "NOP", "RLA", "RRA",
+    "SAX", "SHA", "SHX", "SHY", "SLO", "SRE", "TAS",
+];
+
+const IMP: AddressingMode = AddressingMode::Implied;
+const ACC: AddressingMode = AddressingMode::Accumulator;
+const IMM: AddressingMode = AddressingMode::Immediate;
+const ZP: AddressingMode = AddressingMode::ZeroPage;
+const ZPX: AddressingMode = AddressingMode::ZeroPageX;
+const ZPY: AddressingMode = AddressingMode::ZeroPageY;
+const ABS: AddressingMode = AddressingMode::Absolute;
+const ABX: AddressingMode = AddressingMode::AbsoluteX;
+const ABY: AddressingMode = AddressingMode::AbsoluteY;
+const IND: AddressingMode = AddressingMode::Indirect;
+const IZX: AddressingMode = AddressingMode::IndirectX;
+const IZY: AddressingMode = AddressingMode::IndirectY;
+const REL: AddressingMode = AddressingMode::Relative;
+
+/// Mnemonic and addressing mode of each opcode
+#[rustfmt::skip]
+const OPCODES: [(&str, AddressingMode); 256] = [
+    // $00
+    ("BRK", IMP), ("ORA", IZX), ("JAM", IMP), ("SLO", IZX),
+    ("NOP", ZP), ("ORA", ZP), ("ASL", ZP), ("SLO", ZP),
+    ("PHP", IMP), ("ORA", IMM), ("ASL", ACC), ("ANC", IMM),
+    ("NOP", ABS), ("ORA", ABS), ("ASL", ABS), ("SLO", ABS),
+    // $10
+    ("BPL", REL), ("ORA", IZY), ("JAM", IMP), ("SLO", IZY),
+    ("NOP", ZPX), ("ORA", ZPX), ("ASL", ZPX), ("SLO", ZPX),
+    ("CLC", IMP), ("ORA", ABY), ("NOP", IMP), ("SLO", ABY),
+    ("NOP", ABX), ("ORA", ABX), ("ASL", ABX), ("SLO", ABX),
+    // $20
+    ("JSR", ABS), ("AND", IZX), ("JAM", IMP), ("RLA", IZX),
+    ("BIT", ZP), ("AND", ZP), ("ROL", ZP), ("RLA", ZP),
+    ("PLP", IMP), ("AND", IMM), ("ROL", ACC), ("ANC", IMM),
+    ("BIT", ABS), ("AND", ABS), ("ROL", ABS), ("RLA", ABS),
+    // $30
+    ("BMI", REL), ("AND", IZY), ("JAM", IMP), ("RLA", IZY),
+    ("NOP", ZPX), ("AND", ZPX), ("ROL", ZPX), ("RLA", ZPX),
+    ("SEC", IMP), ("AND", ABY), ("NOP", IMP), ("RLA", ABY),
+    ("NOP", ABX), ("AND", ABX), ("ROL", ABX), ("RLA", ABX),
+    // $40
+    ("RTI", IMP), ("EOR", IZX), ("JAM", IMP), ("SRE", IZX),
+    ("NOP", ZP), ("EOR", ZP), ("LSR", ZP), ("SRE", ZP),
+    ("PHA", IMP), ("EOR", IMM), ("LSR", ACC), ("ALR", IMM),
+    ("JMP", ABS), ("EOR", ABS), ("LSR", ABS), ("SRE", ABS),
+    // $50
+    ("BVC", REL), ("EOR", IZY), ("JAM", IMP), ("SRE", IZY),
+    ("NOP", ZPX), ("EOR", ZPX), ("LSR", ZPX), ("SRE", ZPX),
+    ("CLI", IMP), ("EOR", ABY), ("NOP", IMP), ("SRE", ABY),
+    ("NOP", ABX), ("EOR", ABX), ("LSR", ABX), ("SRE", ABX),
+    // $60
+    ("RTS", IMP), ("ADC", IZX), ("JAM", IMP), ("RRA", IZX),
+    ("NOP", ZP), ("ADC", ZP), ("ROR", ZP), ("RRA", ZP),
+    ("PLA", IMP), ("ADC", IMM), ("ROR", ACC), ("ARR", IMM),
+    ("JMP", IND), ("ADC", ABS), ("ROR", ABS), ("RRA", ABS),
+    // $70
+    ("BVS", REL), ("ADC", IZY), ("JAM", IMP), ("RRA", IZY),
+    ("NOP", ZPX), ("ADC", ZPX), ("ROR", ZPX), ("RRA", ZPX),
+    ("SEI", IMP), ("ADC", ABY), ("NOP", IMP), ("RRA", ABY),
+    ("NOP", ABX), ("ADC", ABX), ("ROR", ABX), ("RRA", ABX),
+    // $80
+    ("NOP", IMM), ("STA", IZX), ("NOP", IMM), ("SAX", IZX),
+    ("STY", ZP), ("STA", ZP), ("STX", ZP), ("SAX", ZP),
+    ("DEY", IMP), ("NOP", IMM), ("TXA", IMP), ("ANE", IMM),
+    ("STY", ABS), ("STA", ABS), ("STX", ABS), ("SAX", ABS),
+    // $90
+    ("BCC", REL), ("STA", IZY), ("JAM", IMP), ("SHA", IZY),
+    ("STY", ZPX), ("STA", ZPX), ("STX", ZPY), ("SAX", ZPY),
+    ("TYA", IMP), ("STA", ABY), ("TXS", IMP), ("TAS", ABY),
+    ("SHY", ABX), ("STA", ABX), ("SHX", ABY), ("SHA", ABY),
+    // $A0
+    ("LDY", IMM), ("LDA", IZX), ("LDX", IMM), ("LAX", IZX),
+    ("LDY", ZP), ("LDA", ZP), ("LDX", ZP), ("LAX", ZP),
+    ("TAY", IMP), ("LDA", IMM), ("TAX", IMP), ("LAX", IMM),
+    ("LDY", ABS), ("LDA", ABS), ("LDX", ABS), ("LAX", ABS),
+    // $B0
+    ("BCS", REL), ("LDA", IZY), ("JAM", IMP), ("LAX", IZY),
+    ("LDY", ZPX), ("LDA", ZPX), ("LDX", ZPY), ("LAX", ZPY),
+    ("CLV", IMP), ("LDA", ABY), ("TSX", IMP), ("LAS", ABY),
+    ("LDY", ABX), ("LDA", ABX), ("LDX", ABY), ("LAX", ABY),
+    // $C0
+    ("CPY", IMM), ("CMP", IZX), ("NOP", IMM), ("DCP", IZX),
+    ("CPY", ZP), ("CMP", ZP), ("DEC", ZP), ("DCP", ZP),
+    ("INY", IMP), ("CMP", IMM), ("DEX", IMP), ("AXS", IMM),
+    ("CPY", ABS), ("CMP", ABS), ("DEC", ABS), ("DCP", ABS),
+    // $D0
+    ("BNE", REL), ("CMP", IZY), ("JAM", IMP), ("DCP", IZY),
+    ("NOP", ZPX), ("CMP", ZPX), ("DEC", ZPX), ("DCP", ZPX),
+    ("CLD", IMP), ("CMP", ABY), ("NOP", IMP), ("DCP", ABY),
+    ("NOP", ABX), ("CMP", ABX), ("DEC", ABX), ("DCP", ABX),
+    // $E0
+    ("CPX", IMM), ("SBC", IZX), ("NOP", IMM), ("ISC", IZX),
+    ("CPX", ZP), ("SBC", ZP), ("INC", ZP), ("ISC", ZP),
+    ("INX", IMP), ("SBC", IMM), ("NOP", IMP), ("SBC", IMM),
+    ("CPX", ABS), ("SBC", ABS), ("INC", ABS), ("ISC", ABS),
+    // $F0
+    ("BEQ", REL), ("SBC", IZY), ("JAM", IMP), ("ISC", IZY),
+    ("NOP", ZPX), ("SBC", ZPX), ("INC", ZPX), ("ISC", ZPX),
+    ("SED", IMP), ("SBC", ABY), ("NOP", IMP), ("ISC", ABY),
+    ("NOP", ABX), ("SBC", ABX), ("INC", ABX), ("ISC", ABX),
+];
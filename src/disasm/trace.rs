@@ -0,0 +1,347 @@
+//! Separate code from data by following control flow from entry points.
+//!
+//! Only the bank containing the vectors at power on is traced, and its mirrors, as it's usually
+//! the one which can't be switched out. Code in switchable banks depends on bank state at run
+//! time, jumps to them are collected but not followed.
+
+use {
+    super::{AddressingMode, Instruction},
+    crate::{
+        mapper::{BankState, MemoryMap, Window},
+        BankSize, NESFile, Vector, VectorProblem,
+    },
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        ops::Range,
+    },
+};
+
+/// What a PRG-ROM byte is found to be
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ByteKind {
+    /// Not reached by tracing, or out of the traced banks
+    Unknown,
+    /// Part of an instruction
+    Code,
+    /// Read by an instruction, a vector or a pointer of `jmp` indirect
+    Data,
+}
+
+/// Result of [`trace`](fn.trace.html)
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Trace {
+    /// Kind of each PRG-ROM byte
+    pub kinds: Vec<ByteKind>,
+    /// Traced instructions by PRG-ROM offset of the opcode byte
+    pub instructions: BTreeMap<usize, Instruction>,
+    /// PRG-ROM offsets of entry points and jump and branch targets
+    pub labels: BTreeSet<usize>,
+    /// Entry points and targets out of the traced banks, like RAM or switchable banks
+    pub external: BTreeSet<u16>,
+    windows: Vec<Window>,
+}
+
+impl Trace {
+    /// PRG-ROM offset of CPU `address`, `None` if it's not in the traced banks.
+    #[must_use]
+    pub fn offset(&self, address: u16) -> Option<usize> {
+        let address32 = u32::from(address);
+        let window = self.windows.iter().find(|x| x.addresses().contains(&address32))?;
+        Some(window.offset + usize::from(address - window.address))
+    }
+
+    /// CPU address of PRG-ROM `offset`, the highest one if it's mirrored. `None` if it's not in
+    /// the traced banks.
+    #[must_use]
+    pub fn address(&self, offset: usize) -> Option<u16> {
+        let window = self.window(offset)?;
+        #[allow(clippy::cast_possible_truncation)] // window is in 16 bit address space
+        let delta = (offset - window.offset) as u16;
+        Some(window.address + delta)
+    }
+
+    fn window(&self, offset: usize) -> Option<&Window> {
+        self.windows.iter().filter(|x| x.offsets().contains(&offset)).max_by_key(|x| x.address)
+    }
+
+    /// Runs of PRG-ROM bytes with the same kind, in offset order.
+    #[must_use]
+    pub fn ranges(&self) -> Vec<(Range<usize>, ByteKind)> {
+        let mut ranges: Vec<(Range<usize>, ByteKind)> = Vec::new();
+        for (offset, &kind) in self.kinds.iter().enumerate() {
+            match ranges.last_mut() {
+                Some((range, last)) if *last == kind => range.end = offset + 1,
+                _ => ranges.push((offset..offset + 1, kind)),
+            }
+        }
+        ranges
+    }
+
+    /// Write the traced banks of `file` as ca65 source with labels.
+    ///
+    /// Labels are named `L` followed by the address, like `LE124`. A label inside an instruction
+    /// is defined relative to it, like `LE125 = * + 1`. Code is written as instructions, other
+    /// bytes as `.byte` with `data` or `unknown` in comment.
+    #[must_use]
+    pub fn to_ca65(&self, file: &NESFile<'_>) -> String {
+        let mut source = String::new();
+        let windows = self.windows.iter().filter(|x| self.window(x.offset) == Some(x));
+        for window in windows {
+            let line = format!(".org ${:04X}\n", window.address);
+            source.push_str(&line);
+
+            let mut offset = window.offset;
+            let end = usize::min(window.offsets().end, file.prg_rom.len());
+            while offset < end {
+                let address = self.address(offset).unwrap_or_default();
+                if self.labels.contains(&offset) {
+                    let line = format!("{}:\n", label(address));
+                    source.push_str(&line);
+                }
+                if let Some(instruction) = self.instructions.get(&offset) {
+                    // targets inside the instruction, like the skipped operand of a `bit`
+                    for inner in 1..instruction.size() {
+                        if self.labels.contains(&(offset + inner)) {
+                            #[allow(clippy::cast_possible_truncation)] // at most 3 bytes
+                            let name = label(address.wrapping_add(inner as u16));
+                            let line = format!("{name} = * + {inner}\n");
+                            source.push_str(&line);
+                        }
+                    }
+                    source.push_str(&self.instruction_line(instruction));
+                    offset += instruction.size();
+                    continue;
+                }
+
+                let kind = self.kinds[offset];
+                let run_end = (offset + 1..end)
+                    .find(|&x| {
+                        x - offset == 16 || self.kinds[x] != kind || self.labels.contains(&x)
+                    })
+                    .unwrap_or(end);
+                let bytes = super::byte_list(&file.prg_rom[offset..run_end]);
+                let kind = if kind == ByteKind::Data { "data" } else { "unknown" };
+                let line = format!("    .byte {bytes} ; ${address:04X} {kind}\n");
+                source.push_str(&line);
+                offset = run_end;
+            }
+        }
+        source
+    }
+
+    fn instruction_line(&self, instruction: &Instruction) -> String {
+        let address = instruction.address;
+        // a mirrored address would assemble to different bytes
+        let labelled = instruction.target().filter(|&target| {
+            self.offset(target)
+                .is_some_and(|x| self.labels.contains(&x) && self.address(x) == Some(target))
+        });
+        let text = labelled.map_or_else(
+            || instruction.to_string(),
+            |target| {
+                let mnemonic = instruction.opcode.mnemonic.to_ascii_lowercase();
+                format!("{mnemonic} {}", label(target))
+            },
+        );
+        format!("    {text} ; ${address:04X}\n")
+    }
+}
+
+fn label(address: u16) -> String {
+    format!("L{address:04X}")
+}
+
+/// Trace code of `file` from its reset, NMI and IRQ vectors. Blank vectors are skipped.
+///
+/// # Examples
+///
+/// ```rust
+/// use dotnes::disasm::{self, ByteKind};
+///
+/// let data = std::fs::read("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+/// let nes = dotnes::parse(&data).unwrap();
+/// let trace = disasm::trace(&nes);
+/// let reset = trace.offset(0xE124).unwrap();
+/// assert_eq!(trace.kinds[reset], ByteKind::Code);
+/// assert!(trace.labels.contains(&reset));
+/// assert_eq!(trace.kinds[nes.prg_rom.len() - 1], ByteKind::Data);
+/// ```
+#[must_use]
+pub fn trace(file: &NESFile<'_>) -> Trace {
+    let mut entries = Vec::new();
+    if let Some(vectors) = file.vectors() {
+        let blank: Vec<_> = vectors
+            .problems(file)
+            .iter()
+            .filter(|x| matches!(x, VectorProblem::Blank { .. }))
+            .map(VectorProblem::vector)
+            .collect();
+        for &(vector, address) in &[
+            (Vector::Reset, vectors.reset),
+            (Vector::Nmi, vectors.nmi),
+            (Vector::Irq, vectors.irq),
+        ] {
+            if !blank.contains(&vector) {
+                entries.push(address);
+            }
+        }
+    }
+    trace_from(file, &entries)
+}
+
+/// Trace code of `file` from CPU addresses `entries`, like [`trace`](fn.trace.html) but the
+/// entry points are given. The vectors are still marked as data.
+#[must_use]
+pub fn trace_from(file: &NESFile<'_>, entries: &[u16]) -> Trace {
+    let mut tracer = Tracer {
+        prg_rom: file.prg_rom,
+        trace: Trace {
+            kinds: vec![ByteKind::Unknown; file.prg_rom.len()],
+            instructions: BTreeMap::new(),
+            labels: BTreeSet::new(),
+            external: BTreeSet::new(),
+            windows: fixed_windows(file),
+        },
+        pending: Vec::new(),
+    };
+
+    tracer.data(0xFFFA, 6);
+    for &address in entries {
+        tracer.jump(address);
+    }
+    while let Some(offset) = tracer.pending.pop() {
+        tracer.follow(offset);
+    }
+    tracer.trace
+}
+
+/// Windows of the bank to trace, see module document. For mappers not
+/// supported by [`BankState`], the last 16KB of PRG-ROM at $C000.
+fn fixed_windows(file: &NESFile<'_>) -> Vec<Window> {
+    let Some(state) = BankState::power_on(file.header.mapper) else {
+        let size = usize::min(file.prg_rom.len(), BankSize::Size16K.bytes());
+        let Some(size) = BankSize::from_bytes(size) else {
+            return Vec::new();
+        };
+        #[allow(clippy::cast_possible_truncation)] // bank size is at most 16K here
+        let address = (0x1_0000 - size.bytes()) as u16;
+        return vec![Window { address, size, offset: file.prg_rom.len() - size.bytes() }];
+    };
+    let windows = MemoryMap::for_file(file, &state).prg;
+    let Some(fixed) = windows.iter().find(|x| x.addresses().contains(&0xFFFC)).copied() else {
+        return Vec::new();
+    };
+    windows.into_iter().filter(|x| x.offsets() == fixed.offsets()).collect()
+}
+
+/// State while tracing
+struct Tracer<'a> {
+    prg_rom: &'a [u8],
+    trace: Trace,
+    /// Offsets of labels not followed yet
+    pending: Vec<usize>,
+}
+
+impl Tracer<'_> {
+    /// Add a jump target, follow it later if it's in the traced banks
+    fn jump(&mut self, address: u16) {
+        match self.trace.offset(address) {
+            Some(offset) => {
+                if self.trace.labels.insert(offset) {
+                    self.pending.push(offset);
+                }
+            }
+            None => {
+                self.trace.external.insert(address);
+            }
+        }
+    }
+
+    /// Mark `count` bytes from `address` as data, unless they are code
+    fn data(&mut self, address: u16, count: u16) {
+        for i in 0..count {
+            if let Some(offset) = self.trace.offset(address.wrapping_add(i)) {
+                if self.trace.kinds[offset] == ByteKind::Unknown {
+                    self.trace.kinds[offset] = ByteKind::Data;
+                }
+            }
+        }
+    }
+
+    /// Decode instructions from `offset` until control flow doesn't continue to the next one,
+    /// or the bytes are not valid code
+    fn follow(&mut self, mut offset: usize) {
+        while !self.trace.instructions.contains_key(&offset) {
+            let Some(instruction) = self.decode(offset) else {
+                return;
+            };
+            let range = offset..offset + instruction.size();
+            if self.trace.kinds[range.clone()].contains(&ByteKind::Code) {
+                // overlaps another instruction
+                return;
+            }
+            for kind in &mut self.trace.kinds[range] {
+                *kind = ByteKind::Code;
+            }
+            self.trace.instructions.insert(offset, instruction);
+            if !self.execute(&instruction) {
+                return;
+            }
+            offset += instruction.size();
+        }
+    }
+
+    /// Official instruction at `offset` which doesn't cross the end of its window
+    fn decode(&self, offset: usize) -> Option<Instruction> {
+        let window = self.trace.window(offset)?;
+        let end = usize::min(window.offsets().end, self.prg_rom.len());
+        let address = self.trace.address(offset)?;
+        let instruction = Instruction::decode(self.prg_rom.get(offset..end)?, address)?;
+        Some(instruction).filter(|x| x.opcode.official)
+    }
+
+    /// Record targets and data of `instruction`, return if the next instruction is executed
+    fn execute(&mut self, instruction: &Instruction) -> bool {
+        match instruction.opcode.mnemonic {
+            "JMP" => {
+                match instruction.target() {
+                    Some(target) => self.jump(target),
+                    None => self.data(instruction.operand, 2),
+                }
+                false
+            }
+            "RTS" | "RTI" | "BRK" => false,
+            mnemonic => {
+                if let Some(target) = instruction.target() {
+                    self.jump(target);
+                } else if is_read(mnemonic) && is_absolute(instruction.opcode.mode) {
+                    self.data(instruction.operand, 1);
+                }
+                true
+            }
+        }
+    }
+}
+
+/// If the instruction reads its operand address, writes to ROM are mapper registers
+fn is_read(mnemonic: &str) -> bool {
+    matches!(
+        mnemonic,
+        "LDA"
+            | "LDX"
+            | "LDY"
+            | "ADC"
+            | "SBC"
+            | "AND"
+            | "ORA"
+            | "EOR"
+            | "CMP"
+            | "CPX"
+            | "CPY"
+            | "BIT"
+    )
+}
+
+const fn is_absolute(mode: AddressingMode) -> bool {
+    matches!(mode, AddressingMode::Absolute | AddressingMode::AbsoluteX | AddressingMode::AbsoluteY)
+}
@@ -0,0 +1,238 @@
+//! Game Genie codes.
+//!
+//! A code replaces the byte the CPU reads at an address in $8000 - $FFFF. 8-letter codes only
+//! replace it when the original byte equals the compare value, so they work with bank switching.
+//! Codes can be resolved to PRG-ROM offsets and applied to a ROM image permanently.
+
+use {
+    crate::{
+        mapper::{BankState, MemoryMap},
+        BankSize, NESFile, ParseError,
+    },
+    std::{error::Error, fmt, str::FromStr},
+};
+
+/// Letters of Game Genie codes, each is a 4 bits value of its index
+const LETTERS: &[u8; 16] = b"APZLGITYEOXUKSVN";
+
+/// Decode or apply code failed reason
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum GameGenieError {
+    /// Code is not 6 or 8 letters
+    InvalidLength {
+        /// Letter count of the code
+        length: usize,
+    },
+    /// Code has a character which is not a Game Genie letter
+    InvalidLetter {
+        /// The invalid character
+        letter: char,
+    },
+    /// ROM image to apply codes can't be parsed
+    Parse(ParseError),
+    /// No PRG-ROM byte is replaced by the code, as the compare value doesn't match
+    NoMatch {
+        /// The code
+        code: Code,
+    },
+}
+
+impl fmt::Display for GameGenieError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength { length } => write!(f, "code has {length} letters, not 6 or 8"),
+            Self::InvalidLetter { letter } => write!(f, "{letter:?} is not a Game Genie letter"),
+            Self::Parse(err) => write!(f, "invalid ROM: {err}"),
+            Self::NoMatch { code } => write!(f, "code {code} matches no PRG-ROM byte"),
+        }
+    }
+}
+
+impl Error for GameGenieError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Parse(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// A decoded Game Genie code
+///
+/// # Examples
+///
+/// ```rust
+/// use dotnes::gamegenie::Code;
+///
+/// let code: Code = "SXIOPO".parse().unwrap();
+/// assert_eq!(code, Code { address: 0x91D9, value: 0xAD, compare: None });
+///
+/// let code = Code { address: 0xD1DD, value: 0x14, compare: Some(0x09) };
+/// assert_eq!(Code::decode(&code.encode()), Ok(code));
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Code {
+    /// CPU address, in $8000 - $FFFF
+    pub address: u16,
+    /// Value read instead of the original byte
+    pub value: u8,
+    /// Only replace when the original byte is this value, `Some` for 8-letter codes
+    pub compare: Option<u8>,
+}
+
+impl Code {
+    /// Decode a 6 or 8 letters code, letters are case insensitive.
+    ///
+    /// # Errors
+    ///
+    /// Return [`GameGenieError`](enum.GameGenieError.html) if the length or a letter is invalid.
+    pub fn decode(text: &str) -> Result<Self, GameGenieError> {
+        let mut n = [0_u16; 8];
+        let mut length = 0;
+        for letter in text.chars() {
+            let upper = letter.to_ascii_uppercase();
+            let index = LETTERS.iter().position(|&x| char::from(x) == upper);
+            let index = index.ok_or(GameGenieError::InvalidLetter { letter })?;
+            if let Some(nibble) = n.get_mut(length) {
+                #[allow(clippy::cast_possible_truncation)] // less than 16
+                let index = index as u16;
+                *nibble = index;
+            }
+            length += 1;
+        }
+        if length != 6 && length != 8 {
+            return Err(GameGenieError::InvalidLength { length });
+        }
+
+        let address = 0x8000
+            | ((n[3] & 7) << 12)
+            | ((n[5] & 7) << 8)
+            | ((n[4] & 8) << 8)
+            | ((n[2] & 7) << 4)
+            | ((n[1] & 8) << 4)
+            | (n[4] & 7)
+            | (n[3] & 8);
+        let byte = |high: u16, low: u16, bit3: u16| {
+            #[allow(clippy::cast_possible_truncation)] // 8 bits
+            let byte = (((high & 7) << 4) | ((low & 8) << 4) | (low & 7) | (bit3 & 8)) as u8;
+            byte
+        };
+        let (value, compare) = if length == 6 {
+            (byte(n[1], n[0], n[5]), None)
+        } else {
+            (byte(n[1], n[0], n[7]), Some(byte(n[7], n[6], n[5])))
+        };
+        Ok(Self { address, value, compare })
+    }
+
+    /// Encode to letters, 8 letters if there is a compare value.
+    #[must_use]
+    pub fn encode(&self) -> String {
+        let a = self.address;
+        let v = u16::from(self.value);
+        let c = u16::from(self.compare.unwrap_or_default());
+        let long = self.compare.is_some();
+
+        let mut n = vec![
+            (v & 7) | ((v >> 4) & 8),
+            ((v >> 4) & 7) | ((a >> 4) & 8),
+            ((a >> 4) & 7) | if long { 8 } else { 0 },
+            ((a >> 12) & 7) | (a & 8),
+            (a & 7) | ((a >> 8) & 8),
+            ((a >> 8) & 7) | if long { c & 8 } else { v & 8 },
+        ];
+        if long {
+            n.push((c & 7) | ((c >> 4) & 8));
+            n.push(((c >> 4) & 7) | (v & 8));
+        }
+        n.into_iter().map(|x| char::from(LETTERS[usize::from(x)])).collect()
+    }
+
+    /// PRG-ROM offsets of `file` the code replaces: the offset of the address in every bank
+    /// which can be mapped there, and whose byte equals the compare value if any.
+    ///
+    /// Only one bank can be mapped at a window the mapper fixes, like $C000 of mapper 2. Otherwise
+    /// every bank of the window size at power on is a candidate, or of 8KB if the mapper is not
+    /// supported. Use [`Layout::prg_rom_to_file`] for file offsets.
+    ///
+    /// [`Layout::prg_rom_to_file`]: ../struct.Layout.html#method.prg_rom_to_file
+    #[must_use]
+    pub fn offsets(&self, file: &NESFile<'_>) -> Vec<usize> {
+        let address = u32::from(self.address);
+        let state = BankState::power_on(file.header.mapper);
+        let fixed = state.and_then(|state| {
+            let windows = state.fixed_prg(file.prg_rom.len());
+            windows.into_iter().find(|x| x.addresses().contains(&address))
+        });
+        let candidates = fixed.map_or_else(
+            || {
+                let window = state.and_then(|state| {
+                    let map = MemoryMap::for_file(file, &state);
+                    map.prg.into_iter().find(|x| x.addresses().contains(&address))
+                });
+                let size = window.map_or(BankSize::Size8K, |x| x.size).bytes();
+                (usize::from(self.address) % size..file.prg_rom.len()).step_by(size).collect()
+            },
+            |window| vec![window.offset + usize::from(self.address - window.address)],
+        );
+
+        candidates
+            .into_iter()
+            .filter(|&offset| offset < file.prg_rom.len())
+            .filter(|&offset| self.compare.map_or(true, |x| file.prg_rom[offset] == x))
+            .collect()
+    }
+}
+
+impl FromStr for Code {
+    type Err = GameGenieError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::decode(s)
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.encode())
+    }
+}
+
+/// Apply `codes` to NES file `data` permanently, return the file offsets changed.
+///
+/// Offsets are found by [`Code::offsets`](struct.Code.html#method.offsets) before any change,
+/// so a code doesn't see the bytes other codes replace.
+///
+/// # Errors
+///
+/// Return [`GameGenieError`](enum.GameGenieError.html) if `data` can't be parsed or a code
+/// matches no byte, nothing is changed then.
+///
+/// # Examples
+///
+/// ```rust
+/// use dotnes::gamegenie::{self, Code};
+///
+/// let mut data = std::fs::read("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+/// // replace `lda #$EA` at $E127 by `lda #$00`
+/// let code = Code { address: 0xE128, value: 0x00, compare: Some(0xEA) };
+/// assert_eq!(gamegenie::apply(&mut data, &[code]), Ok(vec![0x2138]));
+/// assert_eq!(data[0x2137..0x2139], [0xA9, 0x00]);
+/// ```
+pub fn apply(data: &mut [u8], codes: &[Code]) -> Result<Vec<usize>, GameGenieError> {
+    let file = crate::parse(data).map_err(GameGenieError::Parse)?;
+    let mut patches = Vec::new();
+    for &code in codes {
+        let offsets = code.offsets(&file);
+        if offsets.is_empty() {
+            return Err(GameGenieError::NoMatch { code });
+        }
+        let offsets = offsets.into_iter().filter_map(|x| file.layout.prg_rom_to_file(x));
+        patches.extend(offsets.map(|x| (x, code.value)));
+    }
+
+    for &(offset, value) in &patches {
+        data[offset] = value;
+    }
+    Ok(patches.into_iter().map(|(offset, _)| offset).collect())
+}
@@ -0,0 +1,152 @@
+use {
+    super::{
+        super::mapper::{MapperInfo, MirroringControl},
+        Header,
+    },
+    std::fmt,
+};
+
+/// Contradiction between header fields and known mapper constraints
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Lint {
+    /// Mapper is not in [catalog](../mapper/fn.catalog.html), so can't be checked
+    UnknownMapper {
+        /// Mapper number
+        mapper: u16,
+    },
+    /// PRG-ROM is larger than any known board of the mapper
+    PrgRomTooLarge {
+        /// PRG-ROM size in header
+        size: u32,
+        /// Max PRG-ROM size of known boards
+        max: u32,
+    },
+    /// CHR-ROM is larger than any known board of the mapper, max is 0 for CHR-RAM only boards
+    ChrRomTooLarge {
+        /// CHR-ROM size in header
+        size: u32,
+        /// Max CHR-ROM size of known boards
+        max: u32,
+    },
+    /// CHR-RAM is used but boards of the mapper only have CHR-ROM
+    ChrRamOnChrRomBoard,
+    /// Four-screen mirroring is set but no board of the mapper has four-screen VRAM
+    FourScreenUnsupported,
+    /// Battery is set but there is no memory to be backed
+    BatteryWithoutPrgRam,
+    /// Bus conflicts is set but no board of the mapper has bus conflicts
+    UnexpectedBusConflicts,
+    /// NES 2.0 PRG-RAM size (including PRG-NVRAM) differs from all known boards
+    PrgRamSizeMismatch {
+        /// Sum of PRG-RAM and PRG-NVRAM size in header
+        declared: u32,
+        /// PRG-RAM sizes of known boards
+        known: &'static [u32],
+    },
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownMapper { mapper } => write!(f, "mapper {mapper} is unknown"),
+            Self::PrgRomTooLarge { size, max } => {
+                write!(f, "PRG-ROM size {size} is larger than max size {max} of the mapper")
+            }
+            Self::ChrRomTooLarge { size, max } => {
+                write!(f, "CHR-ROM size {size} is larger than max size {max} of the mapper")
+            }
+            Self::ChrRamOnChrRomBoard => f.write_str("CHR-RAM is used on a CHR-ROM only board"),
+            Self::FourScreenUnsupported => {
+                f.write_str("four-screen mirroring is not supported by the mapper")
+            }
+            Self::BatteryWithoutPrgRam => f.write_str("battery is set without PRG-RAM"),
+            Self::UnexpectedBusConflicts => {
+                f.write_str("bus conflicts is set but the mapper has no bus conflicts")
+            }
+            Self::PrgRamSizeMismatch { declared, known } => write!(
+                f,
+                "PRG-RAM size {declared} differs from known boards of the mapper {known:?}"
+            ),
+        }
+    }
+}
+
+impl Header {
+    /// Check header fields against known constraints of its mapper.
+    ///
+    /// Returns empty vec if no problem found.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dotnes::header::Lint;
+    ///
+    /// let data = std::fs::read("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+    /// let mut header = dotnes::parse(&data).unwrap().header;
+    /// assert!(header.lint().is_empty());
+    ///
+    /// header.prg_rom_size = 64 * 1024;
+    /// assert_eq!(header.lint(), vec![Lint::PrgRomTooLarge { size: 65536, max: 32768 }]);
+    /// ```
+    #[must_use]
+    pub fn lint(&self) -> Vec<Lint> {
+        let mut lints = Vec::new();
+
+        let Some(info) = self.mapper_info() else {
+            lints.push(Lint::UnknownMapper { mapper: self.mapper });
+            return lints;
+        };
+
+        if self.prg_rom_size > info.max_prg_rom_size {
+            lints
+                .push(Lint::PrgRomTooLarge { size: self.prg_rom_size, max: info.max_prg_rom_size });
+        }
+
+        if self.chr_rom_size > info.max_chr_rom_size {
+            lints
+                .push(Lint::ChrRomTooLarge { size: self.chr_rom_size, max: info.max_chr_rom_size });
+        }
+
+        if !info.chr_ram && self.uses_chr_ram() {
+            lints.push(Lint::ChrRamOnChrRomBoard);
+        }
+
+        if self.is_four_screen && !info.four_screen && info.mirroring != MirroringControl::Arbitrary
+        {
+            lints.push(Lint::FourScreenUnsupported);
+        }
+
+        if self.has_persistent_memory && !self.has_nvram(info) {
+            lints.push(Lint::BatteryWithoutPrgRam);
+        }
+
+        if self.has_bus_conflicts && !info.bus_conflicts {
+            lints.push(Lint::UnexpectedBusConflicts);
+        }
+
+        if self.is_nes2 {
+            let declared = self.prg_ram_size + self.prg_nvram_size;
+            if declared != 0 && !info.prg_ram_sizes.contains(&declared) {
+                lints.push(Lint::PrgRamSizeMismatch { declared, known: info.prg_ram_sizes });
+            }
+        }
+
+        lints
+    }
+
+    const fn uses_chr_ram(&self) -> bool {
+        if self.is_nes2 {
+            self.chr_ram_size + self.chr_nvram_size > 0
+        } else {
+            self.chr_rom_size == 0
+        }
+    }
+
+    const fn has_nvram(&self, info: &MapperInfo) -> bool {
+        if self.is_nes2 {
+            self.prg_nvram_size + self.chr_nvram_size > 0
+        } else {
+            info.battery
+        }
+    }
+}
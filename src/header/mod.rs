@@ -1,10 +1,17 @@
 //! Struct and Enums to represent information stored in NES file's header segment
 
+mod lint;
 pub(super) mod parser;
+mod writer;
 
-use num_derive::FromPrimitive;
+use {
+    super::mapper::{
+        self, EffectiveMirroring, MapperInfo, MirroringControl, NametableMirroring, Submapper,
+    },
+    num_derive::FromPrimitive,
+};
 
-pub use parser::ParseHeaderError;
+pub use {lint::Lint, parser::ParseHeaderError};
 
 /// Name Table mirroring type
 #[allow(missing_docs)] // because the variant name is clear enough
@@ -276,3 +283,109 @@ pub struct Header {
     /// Required devices when playing this game
     pub default_expansion_device: ExpansionDevice,
 }
+
+impl Header {
+    /// Create a NES 2.0 header for `mapper` with ROM sizes, other fields use the most common
+    /// values: no RAM except 8K CHR-RAM when there is no CHR-ROM, horizontal mirroring, NTSC
+    /// timing and NES console.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dotnes::header::Header;
+    ///
+    /// let header = Header::new(0, 32 * 1024, 8 * 1024);
+    /// assert_eq!(header.chr_ram_size, 0);
+    /// assert!(header.is_nes2);
+    /// ```
+    #[must_use]
+    pub const fn new(mapper: u16, prg_rom_size: u32, chr_rom_size: u32) -> Self {
+        Self {
+            prg_rom_size,
+            chr_rom_size,
+            prg_ram_size: 0,
+            prg_nvram_size: 0,
+            chr_ram_size: if chr_rom_size == 0 { 8 * 1024 } else { 0 },
+            chr_nvram_size: 0,
+            miscellaneous_rom_count: 0,
+            mapper,
+            sub_mapper: 0,
+            is_four_screen: false,
+            has_trainer: false,
+            has_persistent_memory: false,
+            mirroring: Mirroring::HorizontalOrMapperControlled,
+            has_bus_conflicts: false,
+            timing: Timing::NTSC,
+            is_nes2: true,
+            console_type: ConsoleType::NES,
+            default_expansion_device: ExpansionDevice::Unspecified,
+        }
+    }
+
+    /// Look up information of the mapper used by this file,
+    /// see [`mapper::lookup`](../mapper/fn.lookup.html).
+    #[must_use]
+    pub fn mapper_info(&self) -> Option<&'static MapperInfo> {
+        mapper::lookup(self.mapper, self.sub_mapper)
+    }
+
+    /// Interpret submapper number of this file, see
+    /// [`mapper::submapper`](../mapper/fn.submapper.html).
+    #[must_use]
+    pub fn submapper(&self) -> Submapper {
+        mapper::submapper(self)
+    }
+
+    /// Name table mirroring, combining mirroring and four-screen flags with mapper knowledge.
+    ///
+    /// Some mappers reuse the flags for their own meanings, like mapper 30 and 218, and some
+    /// mappers ignore them, like mapper 7 and 77. Unknown mappers are assumed to be
+    /// hardwired as header declared.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dotnes::mapper::{EffectiveMirroring, MirroringControl, NametableMirroring};
+    ///
+    /// let data = std::fs::read("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+    /// let mut header = dotnes::parse(&data).unwrap().header;
+    /// assert_eq!(
+    ///     header.effective_mirroring(),
+    ///     EffectiveMirroring::Fixed(NametableMirroring::Horizontal)
+    /// );
+    ///
+    /// header.mapper = 7;
+    /// assert_eq!(
+    ///     header.effective_mirroring(),
+    ///     EffectiveMirroring::MapperControlled(MirroringControl::SingleScreen)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn effective_mirroring(&self) -> EffectiveMirroring {
+        let vertical = self.mirroring == Mirroring::Vertical;
+        let declared = match (self.is_four_screen, vertical) {
+            (true, _) => NametableMirroring::FourScreen,
+            (false, true) => NametableMirroring::Vertical,
+            (false, false) => NametableMirroring::Horizontal,
+        };
+
+        match self.mapper {
+            30 if self.is_four_screen && !vertical => {
+                EffectiveMirroring::MapperControlled(MirroringControl::SingleScreen)
+            }
+            77 | 99 => EffectiveMirroring::Fixed(NametableMirroring::FourScreen),
+            // CIRAM A10 is connected to PPU A10, A11, A13 or A14 by these two bits
+            218 => EffectiveMirroring::Fixed(match (self.is_four_screen, vertical) {
+                (false, true) => NametableMirroring::Vertical,
+                (false, false) => NametableMirroring::Horizontal,
+                (true, false) => NametableMirroring::SingleScreenUpper,
+                (true, true) => NametableMirroring::SingleScreenLower,
+            }),
+            _ if self.is_four_screen => EffectiveMirroring::Fixed(declared),
+            _ => match self.mapper_info().map(|info| info.mirroring) {
+                None | Some(MirroringControl::Fixed) => EffectiveMirroring::Fixed(declared),
+                Some(control) => EffectiveMirroring::MapperControlled(control),
+            },
+        }
+    }
+}
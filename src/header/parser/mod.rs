@@ -10,17 +10,57 @@ use {
         VsHardwareType, VsInfo, VsPPUType,
     },
     num_traits::FromPrimitive,
+    std::{error::Error, fmt},
 };
 
 /// Parse head failed reason
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum ParseHeaderError {
     /// Bytes doesn't starts with NES file's magic bytes
-    MagicBytesNotMatch,
+    MagicBytesNotMatch {
+        /// Index of the first byte which is different from magic bytes
+        index: usize,
+    },
     /// Timing info stored in flag9 and flag10 is different
-    TwoDifferTiming,
+    TwoDifferTiming {
+        /// Index of the byte which contradicts timing info in flag9
+        index: usize,
+    },
+    /// NES 2.0 ROM size in exponent-multiplier notation doesn't fit in 32 bits
+    RomSizeOverflow {
+        /// Index of the size byte, 4 for PRG-ROM and 5 for CHR-ROM
+        index: usize,
+    },
 }
 
+impl ParseHeaderError {
+    /// Index of the offending byte in header
+    #[must_use]
+    pub const fn index(&self) -> usize {
+        match self {
+            Self::MagicBytesNotMatch { index }
+            | Self::TwoDifferTiming { index }
+            | Self::RomSizeOverflow { index } => *index,
+        }
+    }
+}
+
+impl fmt::Display for ParseHeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MagicBytesNotMatch { index } => {
+                write!(f, "byte {index} does not match NES magic bytes")
+            }
+            Self::TwoDifferTiming { index } => {
+                write!(f, "timing in byte {index} is different from timing in byte 9")
+            }
+            Self::RomSizeOverflow { index } => write!(f, "ROM size in byte {index} is too large"),
+        }
+    }
+}
+
+impl Error for ParseHeaderError {}
+
 const NES_MAGIC_BYTES: &[u8; 4] = b"NES\x1A";
 const NES_V2_IDENTIFIER: u8 = 0b10;
 const KB: u32 = 1 << 10;
@@ -28,8 +68,8 @@ const KB: u32 = 1 << 10;
 #[allow(clippy::similar_names)] // for `rom` and `ram` is similar
 #[allow(clippy::too_many_lines)] // TODO: reduce code lines
 pub fn parse_header(input: &[u8]) -> Result<Header, ParseHeaderError> {
-    if !input.starts_with(NES_MAGIC_BYTES) {
-        return Err(ParseHeaderError::MagicBytesNotMatch);
+    if let Some(index) = NES_MAGIC_BYTES.iter().zip(input).position(|(x, y)| x != y) {
+        return Err(ParseHeaderError::MagicBytesNotMatch { index });
     }
 
     let prg_rom_size = u32::from(input[4]);
@@ -61,31 +101,20 @@ pub fn parse_header(input: &[u8]) -> Result<Header, ParseHeaderError> {
 
     if is_nes2 {
         let [sub_mapper, mapper_high] = v2::flag8(input[8]);
-        let [prg_rom_size_hi, chr_rom_size_hi] = v2::flag9(input[9]);
-        let [prg_ram_shift, prg_nvram_shift] = v2::flag10(input[10]);
-        let [chr_ram_shift, chr_nvram_shift] = v2::flag11(input[11]);
+        let [chr_rom_size_hi, prg_rom_size_hi] = v2::flag9(input[9]);
+        let [prg_nvram_shift, prg_ram_shift] = v2::flag10(input[10]);
+        let [chr_nvram_shift, chr_ram_shift] = v2::flag11(input[11]);
         let [_, timing] = v2::flag12(input[12]);
         let [a, b] = v2::flag13(input[13]);
         let [_, miscellaneous_rom_count] = v2::flag14(input[14]);
-        let [_, default_expansion_device] = v2::flag15(input[14]);
+        let [_, default_expansion_device] = v2::flag15(input[15]);
 
         let mapper = mapper | (u16::from(mapper_high) << 8);
 
-        let prg_rom_size = if prg_rom_size_hi == 0xF {
-            let mm = prg_rom_size & 0x3;
-            let e = prg_rom_size >> 2;
-            (1 << e) * (mm * 2 + 1)
-        } else {
-            (prg_rom_size | (u32::from(prg_rom_size_hi) << 8)) * 16 * KB
-        };
-
-        let chr_rom_size = if chr_rom_size_hi == 0xF {
-            let mm = chr_rom_size & 0x3;
-            let e = chr_rom_size >> 2;
-            (1 << e) * (mm * 2 + 1)
-        } else {
-            (chr_rom_size | ((chr_rom_size as u32) << 8)) * 8 * KB
-        };
+        let prg_rom_size = nes2_rom_size(prg_rom_size, prg_rom_size_hi, 16 * KB)
+            .ok_or(ParseHeaderError::RomSizeOverflow { index: 4 })?;
+        let chr_rom_size = nes2_rom_size(chr_rom_size, chr_rom_size_hi, 8 * KB)
+            .ok_or(ParseHeaderError::RomSizeOverflow { index: 5 })?;
 
         let prg_ram_size = if prg_ram_shift == 0 { 0 } else { 64_u32 << u32::from(prg_ram_shift) };
 
@@ -148,7 +177,7 @@ pub fn parse_header(input: &[u8]) -> Result<Header, ParseHeaderError> {
         let prg_ram_size = u32::from(prg_ram_size) * 8 * KB;
 
         if timing1 != 0 && timing2 != 0 && timing1 != timing2 {
-            return Err(ParseHeaderError::TwoDifferTiming);
+            return Err(ParseHeaderError::TwoDifferTiming { index: 10 });
         }
         let timing = u8::max(timing1, timing2);
         let timing = match timing {
@@ -180,3 +209,14 @@ pub fn parse_header(input: &[u8]) -> Result<Header, ParseHeaderError> {
         })
     }
 }
+
+/// ROM size of NES 2.0 header from size byte `lsb` and nibble `msb` in byte 9, `None` if it
+/// overflows. When `msb` is $F, `lsb` is `EEEEEEMM` meaning `2^E * (MM * 2 + 1)` bytes.
+fn nes2_rom_size(lsb: u32, msb: u8, unit: u32) -> Option<u32> {
+    if msb == 0xF {
+        let multiplier = (lsb & 0x3) * 2 + 1;
+        1_u32.checked_shl(lsb >> 2)?.checked_mul(multiplier)
+    } else {
+        (lsb | (u32::from(msb) << 8)).checked_mul(unit)
+    }
+}
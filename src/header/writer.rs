@@ -0,0 +1,121 @@
+use super::{ConsoleType, Header, Mirroring, Timing};
+
+const NES_MAGIC_BYTES: &[u8; 4] = b"NES\x1A";
+const KB: u32 = 1 << 10;
+
+impl Header {
+    /// Encode header to the 16 bytes at the start of NES file.
+    ///
+    /// NES 2.0 format is used when [`is_nes2`](#structfield.is_nes2) is set, otherwise iNES
+    /// format, which can not store fields like submapper and CHR-RAM size. ROM sizes which
+    /// can't be represented are rounded up.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let data = std::fs::read("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+    /// let nes = dotnes::parse(&data).unwrap();
+    /// assert_eq!(nes.header.to_bytes()[..8], data[..8]);
+    /// ```
+    #[must_use]
+    pub fn to_bytes(&self) -> [u8; 16] {
+        let mut bytes = [0; 16];
+        bytes[..4].copy_from_slice(NES_MAGIC_BYTES);
+
+        #[allow(clippy::cast_possible_truncation)] // mapper is 12 bits
+        let mapper = [self.mapper as u8, (self.mapper >> 8) as u8];
+        bytes[6] = mapper[0] << 4
+            | u8::from(self.is_four_screen) << 3
+            | u8::from(self.has_trainer) << 2
+            | u8::from(self.has_persistent_memory) << 1
+            | u8::from(self.mirroring == Mirroring::Vertical);
+        bytes[7] = mapper[0] & 0xF0
+            | match self.console_type {
+                ConsoleType::NES => 0,
+                ConsoleType::Vs(_) => 1,
+                ConsoleType::PC10 => 2,
+                ConsoleType::Extend(_) => 3,
+            };
+
+        if self.is_nes2 {
+            self.nes2_bytes(&mut bytes, mapper[1]);
+        } else {
+            self.ines_bytes(&mut bytes);
+        }
+
+        bytes
+    }
+
+    fn nes2_bytes(&self, bytes: &mut [u8; 16], mapper_high: u8) {
+        bytes[7] |= 0b1000;
+
+        let (prg_low, prg_high) = rom_size(self.prg_rom_size, 16 * KB);
+        let (chr_low, chr_high) = rom_size(self.chr_rom_size, 8 * KB);
+        bytes[4] = prg_low;
+        bytes[5] = chr_low;
+        bytes[8] = self.sub_mapper << 4 | (mapper_high & 0x0F);
+        bytes[9] = chr_high << 4 | prg_high;
+        bytes[10] = ram_shift(self.prg_nvram_size) << 4 | ram_shift(self.prg_ram_size);
+        bytes[11] = ram_shift(self.chr_nvram_size) << 4 | ram_shift(self.chr_ram_size);
+        bytes[12] = self.timing as u8;
+        bytes[13] = match self.console_type {
+            ConsoleType::Vs(info) => {
+                (info.hardware_type as u8 & 0x0F) << 4 | (info.ppu_type as u8 & 0x0F)
+            }
+            ConsoleType::Extend(extend) => extend as u8 & 0x0F,
+            ConsoleType::NES | ConsoleType::PC10 => 0,
+        };
+        bytes[14] = self.miscellaneous_rom_count & 0b11;
+        bytes[15] = self.default_expansion_device as u8 & 0x3F;
+    }
+
+    fn ines_bytes(&self, bytes: &mut [u8; 16]) {
+        bytes[4] = units(self.prg_rom_size, 16 * KB);
+        bytes[5] = units(self.chr_rom_size, 8 * KB);
+        bytes[8] = units(self.prg_ram_size, 8 * KB);
+        bytes[10] = u8::from(self.has_bus_conflicts) << 5
+            | u8::from(self.prg_ram_size == 0) << 4
+            | match self.timing {
+                Timing::NTSC | Timing::Dendy => 0,
+                Timing::PAL => 2,
+                Timing::MultipleRegion => 1,
+            };
+    }
+}
+
+/// Count of `unit` needed to hold `size`, saturated to a byte
+fn units(size: u32, unit: u32) -> u8 {
+    let count = size.div_ceil(unit);
+    #[allow(clippy::cast_possible_truncation)] // count is limited to 255
+    let count = count.min(0xFF) as u8;
+    count
+}
+
+/// Encode ROM size to LSB byte and MSB nibble, use exponent-multiplier notation when size is
+/// not a multiple of `unit`.
+fn rom_size(size: u32, unit: u32) -> (u8, u8) {
+    let count = size.div_ceil(unit);
+    if size % unit != 0 || count >= 0xF00 {
+        for multiplier in 0..4_u32 {
+            let power = size / (multiplier * 2 + 1);
+            if size % (multiplier * 2 + 1) == 0 && power.is_power_of_two() {
+                #[allow(clippy::cast_possible_truncation)] // exponent is less than 32
+                return ((power.trailing_zeros() << 2 | multiplier) as u8, 0x0F);
+            }
+        }
+    }
+    #[allow(clippy::cast_possible_truncation)] // split into byte and nibble
+    let size = ((count & 0xFF) as u8, (count >> 8).min(0x0E) as u8);
+    size
+}
+
+/// Shift count of RAM size, which is `64 << shift` bytes, 0 for no RAM
+fn ram_shift(size: u32) -> u8 {
+    if size == 0 {
+        return 0;
+    }
+    let shift = size.div_ceil(64).next_power_of_two().trailing_zeros().clamp(1, 15);
+    #[allow(clippy::cast_possible_truncation)] // shift is at most 15
+    let shift = shift as u8;
+    shift
+}
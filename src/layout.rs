@@ -0,0 +1,192 @@
+//! Where each section of a NES file is located
+
+use {
+    super::{Header, ParseError, HEADER_SIZE, TRAINER_SIZE},
+    std::{fmt, ops::Range},
+};
+
+/// Sections of a NES file
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Section {
+    /// The 16 bytes header
+    Header,
+    /// Trainer data
+    Trainer,
+    /// PRG-ROM data
+    PrgRom,
+    /// CHR-ROM data
+    ChrRom,
+    /// Miscellaneous ROMs declared by header
+    MiscellaneousRoms,
+    /// Bytes after CHR-ROM which are not declared by header
+    Trailing,
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Header => "header",
+            Self::Trainer => "trainer",
+            Self::PrgRom => "PRG-ROM",
+            Self::ChrRom => "CHR-ROM",
+            Self::MiscellaneousRoms => "miscellaneous ROMs",
+            Self::Trailing => "trailing bytes",
+        })
+    }
+}
+
+/// File offset ranges of all sections in a NES file
+///
+/// Ranges never exceed the file length, so a section of a truncated file can be shorter than
+/// its header declared size.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Layout {
+    /// Header range, always `0..16` for a complete file
+    pub header: Range<usize>,
+    /// Trainer range, empty if not present
+    pub trainer: Range<usize>,
+    /// PRG-ROM range
+    pub prg_rom: Range<usize>,
+    /// CHR-ROM range
+    pub chr_rom: Range<usize>,
+    /// Miscellaneous ROMs range, only non-empty when header declares miscellaneous ROMs
+    pub miscellaneous_roms: Range<usize>,
+    /// Bytes after CHR-ROM which are not declared by header as miscellaneous ROMs
+    pub trailing: Range<usize>,
+}
+
+impl Layout {
+    /// Compute the layout of a file with `file_len` bytes according its `header`.
+    #[must_use]
+    pub fn new(header: &Header, file_len: usize) -> Self {
+        let clamp = |offset: usize| usize::min(offset, file_len);
+
+        let header_end = clamp(HEADER_SIZE);
+        let trainer_end = clamp(header_end + if header.has_trainer { TRAINER_SIZE } else { 0 });
+        let prg_rom_end = clamp(trainer_end + header.prg_rom_size as usize);
+        let chr_rom_end = clamp(prg_rom_end + header.chr_rom_size as usize);
+
+        let (miscellaneous_roms, trailing) = if header.miscellaneous_rom_count > 0 {
+            (chr_rom_end..file_len, file_len..file_len)
+        } else {
+            (chr_rom_end..chr_rom_end, chr_rom_end..file_len)
+        };
+
+        Self {
+            header: 0..header_end,
+            trainer: header_end..trainer_end,
+            prg_rom: trainer_end..prg_rom_end,
+            chr_rom: prg_rom_end..chr_rom_end,
+            miscellaneous_roms,
+            trailing,
+        }
+    }
+
+    /// Total file length
+    #[must_use]
+    pub const fn file_len(&self) -> usize {
+        self.trailing.end
+    }
+
+    /// Offset range of a section
+    #[must_use]
+    pub fn range(&self, section: Section) -> Range<usize> {
+        match section {
+            Section::Header => self.header.clone(),
+            Section::Trainer => self.trainer.clone(),
+            Section::PrgRom => self.prg_rom.clone(),
+            Section::ChrRom => self.chr_rom.clone(),
+            Section::MiscellaneousRoms => self.miscellaneous_roms.clone(),
+            Section::Trailing => self.trailing.clone(),
+        }
+    }
+
+    /// Find which section a file offset belongs to, and the offset in that section.
+    ///
+    /// Returns `None` if `offset` is out of file.
+    #[must_use]
+    pub fn locate(&self, offset: usize) -> Option<(Section, usize)> {
+        [
+            Section::Header,
+            Section::Trainer,
+            Section::PrgRom,
+            Section::ChrRom,
+            Section::MiscellaneousRoms,
+            Section::Trailing,
+        ]
+        .iter()
+        .find_map(|&section| from_file(&self.range(section), offset).map(|x| (section, x)))
+    }
+
+    /// Translate an offset in PRG-ROM to file offset, `None` if out of PRG-ROM.
+    #[must_use]
+    pub fn prg_rom_to_file(&self, offset: usize) -> Option<usize> {
+        to_file(&self.prg_rom, offset)
+    }
+
+    /// Translate a file offset to offset in PRG-ROM, `None` if not in PRG-ROM.
+    #[must_use]
+    pub fn file_to_prg_rom(&self, offset: usize) -> Option<usize> {
+        from_file(&self.prg_rom, offset)
+    }
+
+    /// Translate an offset in CHR-ROM to file offset, `None` if out of CHR-ROM.
+    #[must_use]
+    pub fn chr_rom_to_file(&self, offset: usize) -> Option<usize> {
+        to_file(&self.chr_rom, offset)
+    }
+
+    /// Translate a file offset to offset in CHR-ROM, `None` if not in CHR-ROM.
+    #[must_use]
+    pub fn file_to_chr_rom(&self, offset: usize) -> Option<usize> {
+        from_file(&self.chr_rom, offset)
+    }
+
+    /// Sections which are shorter than declared in `header`, as `(section, expected, available)`.
+    pub(crate) fn shortages<'a>(
+        &'a self, header: &Header,
+    ) -> impl Iterator<Item = (Section, usize, usize)> + 'a {
+        let expected_sizes = [
+            (Section::Header, HEADER_SIZE),
+            (Section::Trainer, if header.has_trainer { TRAINER_SIZE } else { 0 }),
+            (Section::PrgRom, header.prg_rom_size as usize),
+            (Section::ChrRom, header.chr_rom_size as usize),
+        ];
+
+        IntoIterator::into_iter(expected_sizes).filter_map(move |(section, expected)| {
+            let available = self.range(section).len();
+            if available < expected {
+                Some((section, expected, available))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Check every section has the size declared in `header`.
+    pub(crate) fn check(&self, header: &Header) -> Result<(), ParseError> {
+        match self.shortages(header).next() {
+            Some((section, expected, available)) => {
+                Err(ParseError::Truncated { section, expected, available })
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+fn to_file(section: &Range<usize>, offset: usize) -> Option<usize> {
+    let offset = section.start.checked_add(offset)?;
+    if section.contains(&offset) {
+        Some(offset)
+    } else {
+        None
+    }
+}
+
+fn from_file(section: &Range<usize>, offset: usize) -> Option<usize> {
+    if section.contains(&offset) {
+        Some(offset - section.start)
+    } else {
+        None
+    }
+}
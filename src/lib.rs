@@ -30,12 +30,56 @@
 //! ```
 //!
 //! See document of [`NESFile`](struct.NESFile.html) struct for parse result.
+//!
+//! If you do not want to load the whole file into memory, use
+//! [`parse_reader`](fn.parse_reader.html) to read sections on demand.
+//!
+//! For headerless ROM data, use [`database`](database/index.html) to find the correct header.
+//!
+//! To check a whole ROM collection for duplicates, bad dumps and wrong headers, use
+//! [`audit_dir`](fn.audit_dir.html), or the `dotnes audit` command.
+//!
+//! CHR-ROM tiles can be decoded and encoded by [`chr`](chr/index.html) module, and rendered with
+//! colors from [`palette`](palette/index.html). Enable the `png` feature to write them as PNG.
+//!
+//! PRG-ROM can be disassembled by [`disasm`](disasm/index.html) module, which also separates code
+//! from data by tracing control flow from the vectors. FCEUX Code/Data Logger files are read and
+//! written by [`cdl`](cdl/index.html) module, and debugger symbol files by
+//! [`symbols`](symbols/index.html) module. Game Genie codes can be decoded and applied to ROM
+//! images by [`gamegenie`](gamegenie/index.html) module.
 
+mod audit;
+mod bank;
+pub mod cdl;
+mod checksum;
+pub mod chr;
+pub mod database;
+pub mod disasm;
+pub mod gamegenie;
 pub mod header;
+mod layout;
+pub mod mapper;
+pub mod palette;
+mod reader;
+mod recovery;
+pub mod symbols;
+mod vectors;
 
-pub use header::ParseHeaderError;
+pub use {
+    audit::{audit, audit_dir, Audit, Issue, ScannedFile},
+    bank::{Bank, BankError, BankSize, Banks},
+    checksum::{crc32, sha1},
+    header::ParseHeaderError,
+    layout::{Layout, Section},
+    reader::{parse_reader, NESFileReader, ReadError},
+    recovery::{parse_recover, Missing, Overdump, Recovered},
+    vectors::{Vector, VectorProblem, Vectors},
+};
 
-use header::{parser::parse_header, Header};
+use {
+    header::{parser::parse_header, Header},
+    std::{error::Error, fmt},
+};
 
 /// NES file parse result
 #[derive(Debug, Clone, Hash)]
@@ -49,19 +93,52 @@ pub struct NESFile<'a> {
     /// Main CHR-ROM data
     pub chr_rom: &'a [u8],
     /// Miscellaneous ROMs, not parsed as blocks, you need split it by yourself
-    /// according the header info if you want to use it
+    /// according the header info if you want to use it.
+    ///
+    /// This is all bytes after CHR-ROM, see [`Layout`](struct.Layout.html) if you need to know
+    /// whether they are declared by header or just trailing bytes.
     pub miscellaneous_roms: &'a [u8],
+    /// Where each section comes from in the input bytes
+    pub layout: Layout,
 }
 
 /// Parse failed reason
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum ParseError {
-    /// Data is too short to be a valid nes file
-    NotEnough,
+    /// Data ends before a section is complete
+    Truncated {
+        /// The incomplete section
+        section: Section,
+        /// Section size declared by header
+        expected: usize,
+        /// Bytes actually present for the section
+        available: usize,
+    },
     /// Error happened when parse first 16 bytes header
     InvalidHeader(ParseHeaderError),
 }
 
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated { section, expected, available } => write!(
+                f,
+                "{section} is truncated, expected {expected} bytes but only {available} available"
+            ),
+            Self::InvalidHeader(err) => write!(f, "invalid header: {err}"),
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Truncated { .. } => None,
+            Self::InvalidHeader(err) => Some(err),
+        }
+    }
+}
+
 impl From<ParseHeaderError> for ParseError {
     #[must_use]
     fn from(err: ParseHeaderError) -> Self {
@@ -89,35 +166,134 @@ const TRAINER_SIZE: usize = 512;
 pub fn parse<I: AsRef<[u8]> + ?Sized>(input: &I) -> Result<NESFile<'_>, ParseError> {
     let input = input.as_ref();
 
+    let (header, layout) = parse_layout(input)?;
+    layout.check(&header)?;
+
+    Ok(NESFile::split(input, header, layout))
+}
+
+fn parse_layout(input: &[u8]) -> Result<(Header, Layout), ParseError> {
     if input.len() < HEADER_SIZE {
-        return Err(ParseError::NotEnough);
+        return Err(ParseError::Truncated {
+            section: Section::Header,
+            expected: HEADER_SIZE,
+            available: input.len(),
+        });
     }
 
     let header = parse_header(&input[0..HEADER_SIZE])?;
+    let layout = Layout::new(&header, input.len());
 
-    let prg_rom_start;
-    let trainer = if header.has_trainer {
-        if input.len() < HEADER_SIZE + TRAINER_SIZE {
-            return Err(ParseError::NotEnough);
+    Ok((header, layout))
+}
+
+impl<'a> NESFile<'a> {
+    /// Iterate PRG-ROM in banks of `size`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dotnes::BankSize;
+    ///
+    /// let data = std::fs::read("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+    /// let nes = dotnes::parse(&data).unwrap();
+    /// assert_eq!(nes.prg_banks(BankSize::Size8K).unwrap().count(), 2);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// When PRG-ROM size is not a multiple of `size`, return
+    /// Err([`BankError::NotMultiple`](enum.BankError.html#variant.NotMultiple)).
+    pub fn prg_banks(&self, size: BankSize) -> Result<Banks<'a>, BankError> {
+        Banks::new(self.prg_rom, size)
+    }
+
+    /// Iterate CHR-ROM in banks of `size`.
+    ///
+    /// # Errors
+    ///
+    /// When CHR-ROM size is not a multiple of `size`, return
+    /// Err([`BankError::NotMultiple`](enum.BankError.html#variant.NotMultiple)).
+    pub fn chr_banks(&self, size: BankSize) -> Result<Banks<'a>, BankError> {
+        Banks::new(self.chr_rom, size)
+    }
+
+    /// Last PRG-ROM bank of `size`, which is the fixed bank of most mappers.
+    ///
+    /// # Errors
+    ///
+    /// When PRG-ROM size is not a multiple of `size` or PRG-ROM is empty, return
+    /// Err([`BankError`](enum.BankError.html)).
+    pub fn last_prg_bank(&self, size: BankSize) -> Result<Bank<'a>, BankError> {
+        bank::last_bank(self.prg_rom, size)
+    }
+
+    /// Last CHR-ROM bank of `size`.
+    ///
+    /// # Errors
+    ///
+    /// When CHR-ROM size is not a multiple of `size` or CHR-ROM is empty (the game uses
+    /// CHR-RAM), return Err([`BankError`](enum.BankError.html)).
+    pub fn last_chr_bank(&self, size: BankSize) -> Result<Bank<'a>, BankError> {
+        bank::last_bank(self.chr_rom, size)
+    }
+
+    /// Read NMI, reset and IRQ vectors at $FFFA - $FFFF.
+    ///
+    /// The addresses are translated to PRG-ROM by the mapper's power on bank state, so mappers
+    /// which don't fix the last bank at $E000 - $FFFF, like MMC1, are handled. For mappers not
+    /// supported by [`BankState`](mapper/enum.BankState.html), the last 6 bytes of PRG-ROM are
+    /// used. `None` if the vectors are not in PRG-ROM.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let data = std::fs::read("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+    /// let nes = dotnes::parse(&data).unwrap();
+    /// assert_eq!(nes.vectors().unwrap().reset, 0xE124);
+    /// ```
+    #[must_use]
+    pub fn vectors(&self) -> Option<Vectors> {
+        Vectors::read(self)
+    }
+
+    /// Write the file back to bytes, the header is re-encoded by
+    /// [`Header::to_bytes`](header/struct.Header.html#method.to_bytes).
+    ///
+    /// So header bytes may differ from the parsed input, like iNES bytes 8 and 10 which are
+    /// normalized. Patch the input bytes instead when the file must stay the same.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let data = std::fs::read("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+    /// let nes = dotnes::parse(&data).unwrap();
+    /// let bytes = nes.to_bytes();
+    /// assert_eq!(bytes.len(), data.len());
+    /// assert_eq!(dotnes::parse(&bytes).unwrap().header, nes.header);
+    /// ```
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.header.to_bytes().to_vec();
+        for section in &[self.trainer, self.prg_rom, self.chr_rom, self.miscellaneous_roms] {
+            bytes.extend_from_slice(section);
         }
-        prg_rom_start = HEADER_SIZE + TRAINER_SIZE;
-        &input[HEADER_SIZE..HEADER_SIZE + TRAINER_SIZE]
-    } else {
-        prg_rom_start = HEADER_SIZE;
-        &[]
-    };
-
-    let chr_rom_start = prg_rom_start + header.prg_rom_size as usize;
-    let chr_rom_end = chr_rom_start + header.chr_rom_size as usize;
-
-    if input.len() < chr_rom_end {
-        return Err(ParseError::NotEnough);
+        bytes
     }
 
-    let prg_rom = &input[prg_rom_start..chr_rom_start];
-    let chr_rom = &input[chr_rom_start..chr_rom_end];
+    /// Sections of headerless data, which ROM databases hash: trainer, PRG-ROM, CHR-ROM and
+    /// miscellaneous ROMs declared by header.
+    fn headerless(&self) -> [&'a [u8]; 4] {
+        let miscellaneous_roms = &self.miscellaneous_roms[..self.layout.miscellaneous_roms.len()];
+        [self.trainer, self.prg_rom, self.chr_rom, miscellaneous_roms]
+    }
 
-    let miscellaneous_roms = &input[chr_rom_end..];
+    fn split(input: &'a [u8], header: Header, layout: Layout) -> Self {
+        let trainer = &input[layout.trainer.clone()];
+        let prg_rom = &input[layout.prg_rom.clone()];
+        let chr_rom = &input[layout.chr_rom.clone()];
+        let miscellaneous_roms = &input[layout.chr_rom.end..];
 
-    Ok(NESFile { header, trainer, prg_rom, chr_rom, miscellaneous_roms })
+        Self { header, trainer, prg_rom, chr_rom, miscellaneous_roms, layout }
+    }
 }
@@ -0,0 +1,560 @@
+use super::{
+    BankSize::{Size16K, Size1K, Size2K, Size32K, Size4K, Size8K},
+    ExpansionAudio::{Mmc5, Namco163, Sunsoft5B, Vrc6, Vrc7},
+    MapperInfo,
+    MirroringControl::{
+        self, Arbitrary, Fixed, SingleScreen, Switchable, SwitchableWithSingleScreen,
+    },
+};
+
+const KB: u32 = 1 << 10;
+
+/// Base of boards made by discrete logic chips, with fixed 32K PRG-ROM and 8K CHR-ROM
+const DISCRETE: MapperInfo = MapperInfo {
+    mapper: 0,
+    sub_mapper: None,
+    name: "",
+    boards: &[],
+    manufacturer: "Nintendo",
+    prg_bank_sizes: &[Size32K],
+    chr_bank_sizes: &[Size8K],
+    max_prg_rom_size: 32 * KB,
+    max_chr_rom_size: 8 * KB,
+    chr_ram: false,
+    prg_ram_sizes: &[],
+    battery: false,
+    irq: false,
+    expansion_audio: None,
+    bus_conflicts: false,
+    mirroring: Fixed,
+    four_screen: false,
+};
+
+const MMC1: MapperInfo = MapperInfo {
+    mapper: 1,
+    name: "MMC1",
+    boards: &[
+        "SAROM", "SBROM", "SCROM", "SEROM", "SGROM", "SKROM", "SLROM", "SL1ROM", "SNROM", "SOROM",
+        "SUROM", "SXROM",
+    ],
+    prg_bank_sizes: &[Size16K, Size32K],
+    chr_bank_sizes: &[Size4K, Size8K],
+    max_prg_rom_size: 512 * KB,
+    max_chr_rom_size: 128 * KB,
+    chr_ram: true,
+    prg_ram_sizes: &[8 * KB, 16 * KB, 32 * KB],
+    battery: true,
+    mirroring: SwitchableWithSingleScreen,
+    ..DISCRETE
+};
+
+const UXROM: MapperInfo = MapperInfo {
+    mapper: 2,
+    name: "UxROM",
+    boards: &["UNROM", "UOROM"],
+    prg_bank_sizes: &[Size16K],
+    max_prg_rom_size: 256 * KB,
+    max_chr_rom_size: 0,
+    chr_ram: true,
+    bus_conflicts: true,
+    ..DISCRETE
+};
+
+const CNROM: MapperInfo = MapperInfo {
+    mapper: 3,
+    name: "CNROM",
+    boards: &["CNROM"],
+    max_chr_rom_size: 32 * KB,
+    bus_conflicts: true,
+    ..DISCRETE
+};
+
+const MMC3: MapperInfo = MapperInfo {
+    mapper: 4,
+    name: "MMC3",
+    boards: &[
+        "TBROM", "TEROM", "TFROM", "TGROM", "TKROM", "TLROM", "TL1ROM", "TR1ROM", "TSROM", "TVROM",
+    ],
+    prg_bank_sizes: &[Size8K],
+    chr_bank_sizes: &[Size1K, Size2K],
+    max_prg_rom_size: 512 * KB,
+    max_chr_rom_size: 256 * KB,
+    chr_ram: true,
+    prg_ram_sizes: &[8 * KB],
+    battery: true,
+    irq: true,
+    mirroring: Switchable,
+    four_screen: true,
+    ..DISCRETE
+};
+
+const AXROM: MapperInfo = MapperInfo {
+    mapper: 7,
+    name: "AxROM",
+    boards: &["ANROM", "AN1ROM", "AMROM", "AOROM"],
+    max_prg_rom_size: 256 * KB,
+    max_chr_rom_size: 0,
+    chr_ram: true,
+    bus_conflicts: true,
+    mirroring: SingleScreen,
+    ..DISCRETE
+};
+
+/// Base of Konami VRC2 and other 8K PRG/1K CHR banking mappers
+const VRC2: MapperInfo = MapperInfo {
+    name: "VRC2",
+    manufacturer: "Konami",
+    prg_bank_sizes: &[Size8K],
+    chr_bank_sizes: &[Size1K],
+    max_prg_rom_size: 256 * KB,
+    max_chr_rom_size: 256 * KB,
+    mirroring: Switchable,
+    ..DISCRETE
+};
+
+const VRC4: MapperInfo = MapperInfo {
+    name: "VRC4",
+    max_chr_rom_size: 512 * KB,
+    prg_ram_sizes: &[8 * KB],
+    battery: true,
+    irq: true,
+    mirroring: SwitchableWithSingleScreen,
+    ..VRC2
+};
+
+const VRC6: MapperInfo = MapperInfo {
+    name: "VRC6",
+    prg_bank_sizes: &[Size8K, Size16K],
+    max_chr_rom_size: 256 * KB,
+    expansion_audio: Some(Vrc6),
+    ..VRC4
+};
+
+const BXROM: MapperInfo = MapperInfo {
+    mapper: 34,
+    name: "BNROM / NINA-001",
+    boards: &["BNROM", "NINA-001"],
+    chr_bank_sizes: &[Size4K, Size8K],
+    max_prg_rom_size: 128 * KB,
+    max_chr_rom_size: 64 * KB,
+    chr_ram: true,
+    ..DISCRETE
+};
+
+const IREM_78: MapperInfo = MapperInfo {
+    mapper: 78,
+    name: "Irem 74HC161/32 / Jaleco JF-16",
+    boards: &["Irem 74HC161/32", "JF-16"],
+    manufacturer: "Irem",
+    prg_bank_sizes: &[Size16K],
+    max_prg_rom_size: 128 * KB,
+    max_chr_rom_size: 128 * KB,
+    bus_conflicts: true,
+    mirroring: Switchable,
+    ..DISCRETE
+};
+
+const NAMCO_210: MapperInfo = MapperInfo {
+    mapper: 210,
+    name: "Namco 175/340",
+    boards: &["Namco 175", "Namco 340"],
+    manufacturer: "Namco",
+    prg_bank_sizes: &[Size8K],
+    chr_bank_sizes: &[Size1K],
+    max_prg_rom_size: 512 * KB,
+    max_chr_rom_size: 256 * KB,
+    mirroring: Switchable,
+    ..DISCRETE
+};
+
+const CAMERICA: MapperInfo = MapperInfo {
+    mapper: 71,
+    name: "Camerica BF909x",
+    boards: &["BF9093", "BF9097"],
+    manufacturer: "Camerica",
+    bus_conflicts: false,
+    ..UXROM
+};
+
+const fn sub(info: MapperInfo, sub_mapper: u8, name: &'static str) -> MapperInfo {
+    MapperInfo { sub_mapper: Some(sub_mapper), name, ..info }
+}
+
+const fn vrc(info: MapperInfo, mapper: u16, boards: &'static [&'static str]) -> MapperInfo {
+    MapperInfo { mapper, boards, ..info }
+}
+
+const fn mirroring(info: MapperInfo, mirroring: MirroringControl) -> MapperInfo {
+    MapperInfo { mirroring, ..info }
+}
+
+pub const CATALOG: &[MapperInfo] = &[
+    MapperInfo {
+        name: "NROM",
+        boards: &["NROM-128", "NROM-256", "HROM", "RROM", "SROM"],
+        chr_ram: true,
+        ..DISCRETE
+    },
+    MMC1,
+    MapperInfo {
+        boards: &["SUROM"],
+        max_chr_rom_size: 0,
+        prg_ram_sizes: &[8 * KB],
+        ..sub(MMC1, 1, "MMC1 (SUROM)")
+    },
+    MapperInfo {
+        boards: &["SOROM"],
+        max_prg_rom_size: 256 * KB,
+        max_chr_rom_size: 0,
+        prg_ram_sizes: &[16 * KB],
+        ..sub(MMC1, 2, "MMC1 (SOROM)")
+    },
+    MapperInfo { boards: &["SNROM"], ..sub(MMC1, 3, "MMC1A") },
+    MapperInfo {
+        boards: &["SXROM"],
+        max_chr_rom_size: 0,
+        prg_ram_sizes: &[32 * KB],
+        ..sub(MMC1, 4, "MMC1 (SXROM)")
+    },
+    MapperInfo {
+        boards: &["SEROM", "SHROM", "SH1ROM"],
+        prg_bank_sizes: &[Size32K],
+        max_prg_rom_size: 32 * KB,
+        chr_ram: false,
+        prg_ram_sizes: &[],
+        battery: false,
+        ..sub(MMC1, 5, "MMC1 (fixed PRG)")
+    },
+    UXROM,
+    MapperInfo { bus_conflicts: false, ..sub(UXROM, 1, "UxROM (no bus conflicts)") },
+    sub(UXROM, 2, "UxROM (AND bus conflicts)"),
+    CNROM,
+    MapperInfo { bus_conflicts: false, ..sub(CNROM, 1, "CNROM (no bus conflicts)") },
+    sub(CNROM, 2, "CNROM (AND bus conflicts)"),
+    MMC3,
+    MapperInfo {
+        boards: &["HKROM"],
+        max_prg_rom_size: 128 * KB,
+        chr_ram: false,
+        prg_ram_sizes: &[KB],
+        four_screen: false,
+        ..sub(MMC3, 1, "MMC6")
+    },
+    MapperInfo { boards: &["Acclaim MC-ACC"], manufacturer: "Acclaim", ..sub(MMC3, 3, "MC-ACC") },
+    sub(MMC3, 4, "MMC3A"),
+    MapperInfo {
+        mapper: 5,
+        name: "MMC5",
+        boards: &["EKROM", "ELROM", "ETROM", "EWROM"],
+        prg_bank_sizes: &[Size8K, Size16K, Size32K],
+        chr_bank_sizes: &[Size1K, Size2K, Size4K, Size8K],
+        max_prg_rom_size: 1024 * KB,
+        max_chr_rom_size: 1024 * KB,
+        prg_ram_sizes: &[8 * KB, 16 * KB, 32 * KB, 64 * KB],
+        battery: true,
+        irq: true,
+        expansion_audio: Some(Mmc5),
+        mirroring: Arbitrary,
+        ..DISCRETE
+    },
+    AXROM,
+    MapperInfo { bus_conflicts: false, ..sub(AXROM, 1, "AxROM (no bus conflicts)") },
+    sub(AXROM, 2, "AxROM (AND bus conflicts)"),
+    MapperInfo {
+        mapper: 9,
+        name: "MMC2",
+        boards: &["PNROM", "PEEOROM"],
+        prg_bank_sizes: &[Size8K],
+        chr_bank_sizes: &[Size4K],
+        max_prg_rom_size: 128 * KB,
+        max_chr_rom_size: 128 * KB,
+        mirroring: Switchable,
+        ..DISCRETE
+    },
+    MapperInfo {
+        mapper: 10,
+        name: "MMC4",
+        boards: &["FJROM", "FKROM"],
+        prg_bank_sizes: &[Size16K],
+        chr_bank_sizes: &[Size4K],
+        max_prg_rom_size: 256 * KB,
+        max_chr_rom_size: 128 * KB,
+        prg_ram_sizes: &[8 * KB],
+        battery: true,
+        mirroring: Switchable,
+        ..DISCRETE
+    },
+    MapperInfo {
+        mapper: 11,
+        name: "Color Dreams",
+        boards: &["Color Dreams"],
+        manufacturer: "Color Dreams",
+        max_prg_rom_size: 128 * KB,
+        max_chr_rom_size: 128 * KB,
+        bus_conflicts: true,
+        ..DISCRETE
+    },
+    MapperInfo {
+        mapper: 13,
+        name: "CPROM",
+        boards: &["CPROM"],
+        chr_bank_sizes: &[Size4K],
+        max_chr_rom_size: 0,
+        chr_ram: true,
+        bus_conflicts: true,
+        ..DISCRETE
+    },
+    MapperInfo {
+        mapper: 16,
+        name: "Bandai FCG",
+        boards: &["FCG-1", "FCG-2", "LZ93D50"],
+        manufacturer: "Bandai",
+        prg_bank_sizes: &[Size16K],
+        max_prg_rom_size: 512 * KB,
+        prg_ram_sizes: &[],
+        battery: true,
+        ..VRC4
+    },
+    MapperInfo {
+        mapper: 18,
+        name: "Jaleco SS88006",
+        boards: &["JF-23", "JF-24", "JF-25", "JF-27", "JF-29", "JF-37", "JF-40"],
+        manufacturer: "Jaleco",
+        max_prg_rom_size: 512 * KB,
+        max_chr_rom_size: 256 * KB,
+        ..VRC4
+    },
+    MapperInfo {
+        mapper: 19,
+        name: "Namco 163",
+        boards: &["Namco 129", "Namco 163"],
+        manufacturer: "Namco",
+        max_prg_rom_size: 512 * KB,
+        max_chr_rom_size: 256 * KB,
+        expansion_audio: Some(Namco163),
+        mirroring: Arbitrary,
+        ..VRC4
+    },
+    vrc(VRC4, 21, &["VRC4a", "VRC4c"]),
+    vrc(VRC2, 22, &["VRC2a"]),
+    vrc(MapperInfo { name: "VRC2/VRC4", ..VRC4 }, 23, &["VRC2b", "VRC4e", "VRC4f"]),
+    vrc(VRC6, 24, &["VRC6a"]),
+    vrc(MapperInfo { name: "VRC2/VRC4", ..VRC4 }, 25, &["VRC2c", "VRC4b", "VRC4d"]),
+    vrc(VRC6, 26, &["VRC6b"]),
+    MapperInfo {
+        mapper: 30,
+        name: "UNROM 512",
+        boards: &["UNROM 512"],
+        manufacturer: "RetroUSB",
+        battery: true,
+        four_screen: true,
+        ..MapperInfo { max_prg_rom_size: 512 * KB, ..UXROM }
+    },
+    BXROM,
+    MapperInfo {
+        boards: &["NINA-001"],
+        manufacturer: "AVE",
+        chr_bank_sizes: &[Size4K],
+        max_prg_rom_size: 64 * KB,
+        chr_ram: false,
+        prg_ram_sizes: &[8 * KB],
+        ..sub(BXROM, 1, "NINA-001")
+    },
+    MapperInfo {
+        boards: &["BNROM"],
+        chr_bank_sizes: &[Size8K],
+        max_chr_rom_size: 0,
+        bus_conflicts: true,
+        ..sub(BXROM, 2, "BNROM")
+    },
+    MapperInfo {
+        mapper: 66,
+        name: "GxROM",
+        boards: &["GNROM", "MHROM"],
+        max_prg_rom_size: 128 * KB,
+        max_chr_rom_size: 32 * KB,
+        bus_conflicts: true,
+        ..DISCRETE
+    },
+    MapperInfo {
+        mapper: 69,
+        name: "Sunsoft FME-7",
+        boards: &["JLROM", "JSROM", "BTR", "Sunsoft 5B"],
+        manufacturer: "Sunsoft",
+        max_prg_rom_size: 512 * KB,
+        max_chr_rom_size: 256 * KB,
+        chr_ram: true,
+        expansion_audio: Some(Sunsoft5B),
+        ..VRC4
+    },
+    CAMERICA,
+    mirroring(
+        MapperInfo { boards: &["BF9097"], ..sub(CAMERICA, 1, "Camerica BF9097") },
+        SingleScreen,
+    ),
+    MapperInfo {
+        mapper: 73,
+        name: "VRC3",
+        boards: &["VRC3"],
+        manufacturer: "Konami",
+        max_prg_rom_size: 128 * KB,
+        prg_ram_sizes: &[8 * KB],
+        battery: true,
+        irq: true,
+        bus_conflicts: false,
+        ..UXROM
+    },
+    MapperInfo {
+        mapper: 75,
+        name: "VRC1",
+        boards: &["VRC1"],
+        chr_bank_sizes: &[Size4K],
+        max_prg_rom_size: 128 * KB,
+        max_chr_rom_size: 128 * KB,
+        ..VRC2
+    },
+    MapperInfo {
+        mapper: 77,
+        name: "Irem LROG017",
+        boards: &["LROG017"],
+        manufacturer: "Irem",
+        chr_bank_sizes: &[Size2K],
+        max_prg_rom_size: 128 * KB,
+        max_chr_rom_size: 32 * KB,
+        chr_ram: true,
+        bus_conflicts: true,
+        four_screen: true,
+        ..DISCRETE
+    },
+    IREM_78,
+    mirroring(
+        MapperInfo {
+            boards: &["JF-16"],
+            manufacturer: "Jaleco",
+            ..sub(IREM_78, 1, "Jaleco JF-16")
+        },
+        SingleScreen,
+    ),
+    MapperInfo { boards: &["Irem 74HC161/32"], ..sub(IREM_78, 3, "Irem 74HC161/32") },
+    MapperInfo {
+        mapper: 79,
+        name: "NINA-03/NINA-06",
+        boards: &["NINA-03", "NINA-06"],
+        manufacturer: "AVE",
+        max_prg_rom_size: 64 * KB,
+        max_chr_rom_size: 64 * KB,
+        ..DISCRETE
+    },
+    MapperInfo {
+        mapper: 85,
+        name: "VRC7",
+        boards: &["VRC7a", "VRC7b"],
+        max_prg_rom_size: 512 * KB,
+        max_chr_rom_size: 256 * KB,
+        chr_ram: true,
+        expansion_audio: Some(Vrc7),
+        ..VRC4
+    },
+    MapperInfo {
+        mapper: 87,
+        name: "Jaleco J87",
+        boards: &["JF-05", "JF-06", "JF-07", "JF-08", "JF-09", "JF-10"],
+        manufacturer: "Jaleco",
+        max_chr_rom_size: 32 * KB,
+        ..DISCRETE
+    },
+    MapperInfo {
+        mapper: 94,
+        name: "UN1ROM",
+        boards: &["UN1ROM"],
+        max_prg_rom_size: 128 * KB,
+        ..UXROM
+    },
+    MapperInfo {
+        mapper: 99,
+        name: "Vs. System",
+        boards: &["Vs. System"],
+        prg_bank_sizes: &[Size8K],
+        max_prg_rom_size: 40 * KB,
+        max_chr_rom_size: 16 * KB,
+        prg_ram_sizes: &[2 * KB],
+        four_screen: true,
+        ..DISCRETE
+    },
+    MapperInfo {
+        mapper: 118,
+        name: "TxSROM",
+        boards: &["TKSROM", "TLSROM"],
+        max_chr_rom_size: 128 * KB,
+        chr_ram: false,
+        mirroring: Arbitrary,
+        four_screen: false,
+        ..MMC3
+    },
+    MapperInfo {
+        mapper: 119,
+        name: "TQROM",
+        boards: &["TQROM"],
+        max_prg_rom_size: 128 * KB,
+        max_chr_rom_size: 64 * KB,
+        prg_ram_sizes: &[],
+        battery: false,
+        four_screen: false,
+        ..MMC3
+    },
+    MapperInfo {
+        mapper: 140,
+        name: "Jaleco JF-11/JF-14",
+        boards: &["JF-11", "JF-14"],
+        manufacturer: "Jaleco",
+        max_prg_rom_size: 128 * KB,
+        max_chr_rom_size: 128 * KB,
+        ..DISCRETE
+    },
+    MapperInfo {
+        mapper: 180,
+        name: "UNROM (fixed first bank)",
+        boards: &["UNROM"],
+        max_prg_rom_size: 128 * KB,
+        ..UXROM
+    },
+    MapperInfo {
+        mapper: 185,
+        name: "CNROM with protection diodes",
+        max_chr_rom_size: 8 * KB,
+        ..CNROM
+    },
+    MapperInfo {
+        mapper: 206,
+        name: "Namco 118 / DxROM",
+        boards: &["DEROM", "DE1ROM", "DRROM", "Namco 108", "Namco 118"],
+        prg_bank_sizes: &[Size8K],
+        chr_bank_sizes: &[Size1K, Size2K],
+        max_prg_rom_size: 128 * KB,
+        max_chr_rom_size: 64 * KB,
+        four_screen: true,
+        ..DISCRETE
+    },
+    NAMCO_210,
+    mirroring(
+        MapperInfo {
+            boards: &["Namco 175"],
+            prg_ram_sizes: &[2 * KB],
+            battery: true,
+            ..sub(NAMCO_210, 1, "Namco 175")
+        },
+        Fixed,
+    ),
+    mirroring(
+        MapperInfo { boards: &["Namco 340"], ..sub(NAMCO_210, 2, "Namco 340") },
+        SwitchableWithSingleScreen,
+    ),
+    MapperInfo {
+        mapper: 218,
+        name: "Magic Floor",
+        boards: &["Magic Floor"],
+        manufacturer: "Homebrew",
+        max_chr_rom_size: 0,
+        ..DISCRETE
+    },
+];
@@ -0,0 +1,480 @@
+use {
+    super::{
+        super::header::{Header, Mirroring},
+        BankState, BusConflicts, EffectiveMirroring, MemoryMap, MirroringControl, Mmc3Variant,
+        Submapper,
+    },
+    std::fmt,
+};
+
+/// Name table mirroring at a moment
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum NametableMirroring {
+    /// $2000 = $2400, $2800 = $2C00
+    Horizontal,
+    /// $2000 = $2800, $2400 = $2C00
+    Vertical,
+    /// All name tables use the lower 1K of CIRAM
+    SingleScreenLower,
+    /// All name tables use the upper 1K of CIRAM
+    SingleScreenUpper,
+    /// Four separate name tables, using extra VRAM on cartridge
+    FourScreen,
+}
+
+/// Emulation of mapper registers
+///
+/// Create one by [`create`](fn.create.html), then feed it CPU writes and PPU events.
+pub trait Mapper: fmt::Debug {
+    /// Handle a CPU write to cartridge address space ($4020 - $FFFF).
+    ///
+    /// `value` should be the value on data bus, see
+    /// [`write_rom`](#method.write_rom) if the board has bus conflicts.
+    fn write(&mut self, address: u16, value: u8);
+
+    /// Current bank registers, use [`MemoryMap`](struct.MemoryMap.html) to get windows.
+    fn bank_state(&self) -> BankState;
+
+    /// Current name table mirroring
+    fn mirroring(&self) -> NametableMirroring;
+
+    /// If the board has bus conflicts, decided by header when created
+    fn bus_conflicts(&self) -> bool {
+        false
+    }
+
+    /// If IRQ line is asserted
+    fn irq(&self) -> bool {
+        false
+    }
+
+    /// Notify a PPU scanline is rendered, clocks scanline counters like MMC3's.
+    fn clock_scanline(&mut self) {}
+
+    /// Notify a CPU cycle passed, clocks cycle counters like FME-7's.
+    fn clock_cpu(&mut self) {}
+
+    /// Notify PPU read `address`, updates CHR latches like MMC2's.
+    fn ppu_read(&mut self, _address: u16) {}
+
+    /// Handle a CPU write like [`write`](#method.write), but apply bus conflicts with ROM
+    /// byte at `address` in `prg_rom` if the board has them.
+    fn write_rom(&mut self, prg_rom: &[u8], address: u16, value: u8) {
+        let mut value = value;
+        if self.bus_conflicts() {
+            let map = MemoryMap::new(&self.bank_state(), prg_rom.len(), 0);
+            if let Some(&rom) = map.cpu_to_prg_rom(address).and_then(|x| prg_rom.get(x)) {
+                value &= rom;
+            }
+        }
+        self.write(address, value);
+    }
+}
+
+/// Create mapper emulation for `header`, `None` if mapper is not supported.
+///
+/// Supported mappers are 0 - 4, 7, 9, 10, 11, 66 and 69.
+///
+/// # Examples
+///
+/// ```rust
+/// use dotnes::mapper::{self, MemoryMap};
+///
+/// let data = std::fs::read("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+/// let nes = dotnes::parse(&data).unwrap();
+/// let mut mapper = mapper::create(&nes.header).unwrap();
+/// mapper.write(0x8000, 0);
+/// let map = MemoryMap::for_file(&nes, &mapper.bank_state());
+/// assert_eq!(map.cpu_to_prg_rom(0xC000), Some(0));
+/// ```
+#[must_use]
+pub fn create(header: &Header) -> Option<Box<dyn Mapper>> {
+    let mirroring = initial_mirroring(header);
+    let state = BankState::power_on(header.mapper)?;
+
+    Some(match state {
+        BankState::Nrom
+        | BankState::UxRom { .. }
+        | BankState::CnRom { .. }
+        | BankState::AxRom { .. }
+        | BankState::GxRom { .. } => Box::new(Discrete {
+            mapper: header.mapper,
+            state,
+            mirroring,
+            bus_conflicts: bus_conflicts(header),
+        }),
+        BankState::Mmc1 { control, .. } => {
+            Box::new(Mmc1 { state, shift: 0, count: 0, control, chr0: 0, chr1: 0, prg: 0 })
+        }
+        BankState::Mmc3 { bank_select, registers } => {
+            let old_irq = matches!(
+                header.submapper(),
+                Submapper::Mmc3(Mmc3Variant::Mmc3A | Mmc3Variant::McAcc)
+            );
+            Box::new(Mmc3::new(bank_select, registers, mirroring, old_irq))
+        }
+        BankState::Mmc2 { .. } | BankState::Mmc4 { .. } => Box::new(Mmc2 {
+            mmc4: header.mapper == 10,
+            prg: 0,
+            chr: [[0; 2]; 2],
+            latches: [1; 2],
+            mirroring,
+        }),
+        BankState::Fme7 { prg, chr } => Box::new(Fme7 {
+            command: 0,
+            prg,
+            chr,
+            mirroring,
+            irq_enabled: false,
+            counter_enabled: false,
+            counter: 0,
+            irq: false,
+        }),
+        _ => return None,
+    })
+}
+
+fn initial_mirroring(header: &Header) -> NametableMirroring {
+    match header.effective_mirroring() {
+        EffectiveMirroring::Fixed(mirroring) => mirroring,
+        EffectiveMirroring::MapperControlled(MirroringControl::SingleScreen) => {
+            NametableMirroring::SingleScreenLower
+        }
+        EffectiveMirroring::MapperControlled(_) if header.mirroring == Mirroring::Vertical => {
+            NametableMirroring::Vertical
+        }
+        EffectiveMirroring::MapperControlled(_) => NametableMirroring::Horizontal,
+    }
+}
+
+fn bus_conflicts(header: &Header) -> bool {
+    match header.submapper() {
+        Submapper::Discrete(BusConflicts::None) => false,
+        Submapper::Discrete(BusConflicts::And) => true,
+        _ => header.has_bus_conflicts || header.mapper_info().is_some_and(|x| x.bus_conflicts),
+    }
+}
+
+/// Boards made by discrete logic, which latch the whole written byte
+#[derive(Debug)]
+struct Discrete {
+    mapper: u16,
+    state: BankState,
+    mirroring: NametableMirroring,
+    bus_conflicts: bool,
+}
+
+impl Mapper for Discrete {
+    fn write(&mut self, address: u16, value: u8) {
+        if address < 0x8000 {
+            return;
+        }
+        match &mut self.state {
+            BankState::Nrom => {}
+            BankState::UxRom { prg } => *prg = value,
+            BankState::CnRom { chr } => *chr = value,
+            BankState::AxRom { prg } => {
+                *prg = value & 0x07;
+                self.mirroring = if value & 0x10 == 0 {
+                    NametableMirroring::SingleScreenLower
+                } else {
+                    NametableMirroring::SingleScreenUpper
+                };
+            }
+            // Color Dreams puts PRG in low bits, GxROM puts CHR in low bits
+            BankState::GxRom { prg, chr } if self.mapper == 11 => {
+                *prg = value & 0x03;
+                *chr = value >> 4;
+            }
+            BankState::GxRom { prg, chr } => {
+                *prg = (value >> 4) & 0x03;
+                *chr = value & 0x03;
+            }
+            _ => unreachable!("discrete boards only use discrete bank states"),
+        }
+    }
+
+    fn bank_state(&self) -> BankState {
+        self.state
+    }
+
+    fn mirroring(&self) -> NametableMirroring {
+        self.mirroring
+    }
+
+    fn bus_conflicts(&self) -> bool {
+        self.bus_conflicts
+    }
+}
+
+/// MMC1, registers are written serially by bit 0 of five writes
+#[derive(Debug)]
+struct Mmc1 {
+    state: BankState,
+    shift: u8,
+    count: u8,
+    control: u8,
+    chr0: u8,
+    chr1: u8,
+    prg: u8,
+}
+
+impl Mapper for Mmc1 {
+    fn write(&mut self, address: u16, value: u8) {
+        if address < 0x8000 {
+            return;
+        }
+        if value & 0x80 != 0 {
+            self.shift = 0;
+            self.count = 0;
+            self.control |= 0x0C;
+        } else {
+            self.shift |= (value & 1) << self.count;
+            self.count += 1;
+            if self.count == 5 {
+                let register = match (address >> 13) & 0b11 {
+                    0 => &mut self.control,
+                    1 => &mut self.chr0,
+                    2 => &mut self.chr1,
+                    _ => &mut self.prg,
+                };
+                *register = self.shift;
+                self.shift = 0;
+                self.count = 0;
+            }
+        }
+        self.state = BankState::Mmc1 {
+            control: self.control,
+            chr0: self.chr0,
+            chr1: self.chr1,
+            prg: self.prg,
+        };
+    }
+
+    fn bank_state(&self) -> BankState {
+        self.state
+    }
+
+    fn mirroring(&self) -> NametableMirroring {
+        match self.control & 0b11 {
+            0 => NametableMirroring::SingleScreenLower,
+            1 => NametableMirroring::SingleScreenUpper,
+            2 => NametableMirroring::Vertical,
+            _ => NametableMirroring::Horizontal,
+        }
+    }
+}
+
+/// MMC3, with scanline counter IRQ
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug)]
+struct Mmc3 {
+    bank_select: u8,
+    registers: [u8; 8],
+    mirroring: NametableMirroring,
+    irq_latch: u8,
+    irq_counter: u8,
+    irq_reload: bool,
+    irq_enabled: bool,
+    irq: bool,
+    /// MMC3A style IRQ, which is not triggered when counter is reloaded with 0
+    old_irq: bool,
+}
+
+impl Mmc3 {
+    const fn new(
+        bank_select: u8, registers: [u8; 8], mirroring: NametableMirroring, old_irq: bool,
+    ) -> Self {
+        Self {
+            bank_select,
+            registers,
+            mirroring,
+            irq_latch: 0,
+            irq_counter: 0,
+            irq_reload: false,
+            irq_enabled: false,
+            irq: false,
+            old_irq,
+        }
+    }
+}
+
+impl Mapper for Mmc3 {
+    fn write(&mut self, address: u16, value: u8) {
+        let even = address & 1 == 0;
+        match (address, even) {
+            (0x8000..=0x9FFF, true) => self.bank_select = value,
+            (0x8000..=0x9FFF, false) => {
+                self.registers[usize::from(self.bank_select & 0x07)] = value;
+            }
+            (0xA000..=0xBFFF, true) if self.mirroring != NametableMirroring::FourScreen => {
+                self.mirroring = if value & 1 == 0 {
+                    NametableMirroring::Vertical
+                } else {
+                    NametableMirroring::Horizontal
+                };
+            }
+            (0xC000..=0xDFFF, true) => self.irq_latch = value,
+            (0xC000..=0xDFFF, false) => {
+                self.irq_counter = 0;
+                self.irq_reload = true;
+            }
+            (0xE000..=0xFFFF, true) => {
+                self.irq_enabled = false;
+                self.irq = false;
+            }
+            (0xE000..=0xFFFF, false) => self.irq_enabled = true,
+            _ => {}
+        }
+    }
+
+    fn bank_state(&self) -> BankState {
+        BankState::Mmc3 { bank_select: self.bank_select, registers: self.registers }
+    }
+
+    fn mirroring(&self) -> NametableMirroring {
+        self.mirroring
+    }
+
+    fn irq(&self) -> bool {
+        self.irq
+    }
+
+    fn clock_scanline(&mut self) {
+        let before = self.irq_counter;
+        let reloaded = self.irq_reload;
+        if self.irq_counter == 0 || self.irq_reload {
+            self.irq_counter = self.irq_latch;
+            self.irq_reload = false;
+        } else {
+            self.irq_counter -= 1;
+        }
+        if self.irq_counter == 0 && self.irq_enabled && (!self.old_irq || before != 0 || reloaded) {
+            self.irq = true;
+        }
+    }
+}
+
+/// MMC2 and MMC4, CHR banks are switched by latches which PPU reads of special tiles set
+#[derive(Debug)]
+struct Mmc2 {
+    mmc4: bool,
+    prg: u8,
+    /// CHR registers for each 4K window, selected by latch
+    chr: [[u8; 2]; 2],
+    /// 0 after tile $FD is read, 1 after tile $FE
+    latches: [usize; 2],
+    mirroring: NametableMirroring,
+}
+
+impl Mapper for Mmc2 {
+    fn write(&mut self, address: u16, value: u8) {
+        match address {
+            0xA000..=0xAFFF => self.prg = value & 0x0F,
+            0xB000..=0xBFFF => self.chr[0][0] = value & 0x1F,
+            0xC000..=0xCFFF => self.chr[0][1] = value & 0x1F,
+            0xD000..=0xDFFF => self.chr[1][0] = value & 0x1F,
+            0xE000..=0xEFFF => self.chr[1][1] = value & 0x1F,
+            0xF000..=0xFFFF => {
+                self.mirroring = if value & 1 == 0 {
+                    NametableMirroring::Vertical
+                } else {
+                    NametableMirroring::Horizontal
+                };
+            }
+            _ => {}
+        }
+    }
+
+    fn bank_state(&self) -> BankState {
+        let prg = self.prg;
+        let chr = [self.chr[0][self.latches[0]], self.chr[1][self.latches[1]]];
+        if self.mmc4 {
+            BankState::Mmc4 { prg, chr }
+        } else {
+            BankState::Mmc2 { prg, chr }
+        }
+    }
+
+    fn mirroring(&self) -> NametableMirroring {
+        self.mirroring
+    }
+
+    fn ppu_read(&mut self, address: u16) {
+        // MMC2 only watches a single address for the first pattern table
+        let first = if self.mmc4 { 0x0FD8..=0x0FDF } else { 0x0FD8..=0x0FD8 };
+        let second = if self.mmc4 { 0x0FE8..=0x0FEF } else { 0x0FE8..=0x0FE8 };
+        match address {
+            x if first.contains(&x) => self.latches[0] = 0,
+            x if second.contains(&x) => self.latches[0] = 1,
+            0x1FD8..=0x1FDF => self.latches[1] = 0,
+            0x1FE8..=0x1FEF => self.latches[1] = 1,
+            _ => {}
+        }
+    }
+}
+
+/// Sunsoft FME-7, registers are written by command and parameter, with CPU cycle IRQ counter
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug)]
+struct Fme7 {
+    command: u8,
+    prg: [u8; 3],
+    chr: [u8; 8],
+    mirroring: NametableMirroring,
+    irq_enabled: bool,
+    counter_enabled: bool,
+    counter: u16,
+    irq: bool,
+}
+
+impl Mapper for Fme7 {
+    fn write(&mut self, address: u16, value: u8) {
+        match address {
+            0x8000..=0x9FFF => self.command = value & 0x0F,
+            0xA000..=0xBFFF => match self.command {
+                // $6000 PRG-RAM/ROM bank is not a ROM window in memory map
+                command @ 0..=7 => self.chr[usize::from(command)] = value,
+                8 => {}
+                command @ 9..=0x0B => self.prg[usize::from(command - 9)] = value & 0x3F,
+                0x0C => {
+                    self.mirroring = match value & 0b11 {
+                        0 => NametableMirroring::Vertical,
+                        1 => NametableMirroring::Horizontal,
+                        2 => NametableMirroring::SingleScreenLower,
+                        _ => NametableMirroring::SingleScreenUpper,
+                    };
+                }
+                0x0D => {
+                    self.irq_enabled = value & 0x01 != 0;
+                    self.counter_enabled = value & 0x80 != 0;
+                    self.irq = false;
+                }
+                0x0E => self.counter = (self.counter & 0xFF00) | u16::from(value),
+                _ => self.counter = (self.counter & 0x00FF) | (u16::from(value) << 8),
+            },
+            _ => {}
+        }
+    }
+
+    fn bank_state(&self) -> BankState {
+        BankState::Fme7 { prg: self.prg, chr: self.chr }
+    }
+
+    fn mirroring(&self) -> NametableMirroring {
+        self.mirroring
+    }
+
+    fn irq(&self) -> bool {
+        self.irq
+    }
+
+    fn clock_cpu(&mut self) {
+        if self.counter_enabled {
+            self.counter = self.counter.wrapping_sub(1);
+            if self.counter == 0xFFFF && self.irq_enabled {
+                self.irq = true;
+            }
+        }
+    }
+}
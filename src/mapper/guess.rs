@@ -0,0 +1,201 @@
+use {
+    super::{super::header::Header, lookup},
+    std::{cmp::Reverse, convert::TryFrom},
+};
+
+/// Clue found in ROM data when guessing mapper
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Evidence {
+    /// No store instruction writes to ROM area $8000 - $FFFF
+    NoRomWrites,
+    /// Store instructions write to ROM area $8000 - $FFFF, which switches banks
+    RomWrites,
+    /// MMC1 style serial writes, `STA`, `LSR A`, `STA` to the same address
+    SerialWrites,
+    /// MMC3 style bank select and bank data pairs, writes to $8000 then $8001
+    SelectDataPairs,
+    /// FME-7 style command and parameter pairs, writes to $8000 then $A000
+    CommandParameterPairs,
+    /// PRG-ROM is at most 32K, which fits CPU address space without bank switching
+    SmallPrg,
+    /// Reset vector points into the last 16K bank, which most mappers fix at $C000
+    ResetInLast16K,
+    /// Reset vector points into the last 8K bank, which MMC3-like mappers fix at $E000
+    ResetInLast8K,
+    /// Every 32K bank has the same reset vector, as games switching whole 32K need
+    ResetInEvery32K,
+    /// No CHR-ROM, the board uses CHR-RAM
+    ChrRam,
+    /// CHR-ROM is larger than 8K, the board switches CHR banks
+    ChrBanks,
+}
+
+/// A candidate header proposed by [`guess`](fn.guess.html)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Guess {
+    /// Proposed header, mirroring is unknown so it's left as default
+    pub header: Header,
+    /// Confidence between 0 and 1, confidences of all candidates sum to 1
+    pub confidence: f64,
+    /// Clues supporting this candidate
+    pub evidence: Vec<Evidence>,
+}
+
+/// Candidate mappers and how much each clue supports them, negative means against
+const WEIGHTS: &[(u16, &[(Evidence, i32)])] = &[
+    (0, &[(Evidence::NoRomWrites, 6), (Evidence::RomWrites, -6), (Evidence::SmallPrg, 4)]),
+    (1, &[(Evidence::SerialWrites, 8), (Evidence::ResetInLast16K, 1)]),
+    (2, &[(Evidence::RomWrites, 3), (Evidence::ResetInLast16K, 2), (Evidence::ChrRam, 1)]),
+    (3, &[(Evidence::RomWrites, 3), (Evidence::ChrBanks, 3), (Evidence::SmallPrg, 2)]),
+    (4, &[(Evidence::SelectDataPairs, 8), (Evidence::ResetInLast8K, 1)]),
+    (7, &[(Evidence::RomWrites, 3), (Evidence::ResetInEvery32K, 3), (Evidence::ChrRam, 1)]),
+    (66, &[(Evidence::RomWrites, 3), (Evidence::ResetInEvery32K, 2), (Evidence::ChrBanks, 2)]),
+    (69, &[(Evidence::CommandParameterPairs, 8), (Evidence::ResetInLast8K, 1)]),
+];
+
+/// Clues which mean a mapper with registers, so discrete logic boards are unlikely
+const REGISTER_CLUES: &[Evidence] =
+    &[Evidence::SerialWrites, Evidence::SelectDataPairs, Evidence::CommandParameterPairs];
+
+/// Max distance in bytes between two writes to be seen as a pair
+const PAIR_DISTANCE: usize = 8;
+
+/// Pattern count needed to believe it's not a coincidence in data
+const MIN_PATTERNS: usize = 2;
+
+/// Guess mapper of headerless ROM data, return candidate headers ranked by confidence.
+///
+/// This scans PRG-ROM for store instructions to ROM area and register write patterns of
+/// common mappers, and checks reset vector and ROM sizes. It's only a heuristic, data bytes
+/// may look like instructions, so check the result by running the game.
+///
+/// Candidates are mapper 0 - 4, 7, 66 and 69, the ones ROM sizes don't fit are excluded.
+///
+/// # Examples
+///
+/// ```rust
+/// let data = std::fs::read("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+/// let nes = dotnes::parse(&data).unwrap();
+/// let guesses = dotnes::mapper::guess(nes.prg_rom, nes.chr_rom);
+/// assert_eq!(guesses[0].header.mapper, 0);
+/// ```
+#[must_use]
+pub fn guess(prg_rom: &[u8], chr_rom: &[u8]) -> Vec<Guess> {
+    let clues = clues(prg_rom, chr_rom);
+    let has_register_clue = REGISTER_CLUES.iter().any(|x| clues.contains(x));
+
+    let mut candidates: Vec<_> = WEIGHTS
+        .iter()
+        .filter(|(mapper, _)| fits(*mapper, prg_rom.len(), chr_rom.len()))
+        .filter_map(|(mapper, weights)| {
+            let matched: Vec<_> = weights.iter().filter(|(x, _)| clues.contains(x)).collect();
+            let mut score: i32 = matched.iter().map(|(_, weight)| weight).sum();
+            if has_register_clue && !weights.iter().any(|(x, _)| REGISTER_CLUES.contains(x)) {
+                score -= 4;
+            }
+            let evidence = matched.iter().filter(|(_, weight)| *weight > 0).map(|(x, _)| *x);
+            u32::try_from(score).ok().filter(|&x| x > 0).map(|x| (*mapper, x, evidence.collect()))
+        })
+        .collect();
+    candidates.sort_by_key(|(mapper, score, _)| (Reverse(*score), *mapper));
+
+    let total: u32 = candidates.iter().map(|(_, score, _)| score).sum();
+    candidates
+        .into_iter()
+        .map(|(mapper, score, evidence)| {
+            #[allow(clippy::cast_possible_truncation)] // ROM size is checked by `fits`
+            let mut header = Header::new(mapper, prg_rom.len() as u32, chr_rom.len() as u32);
+            header.prg_ram_size =
+                lookup(mapper, 0).and_then(|x| x.prg_ram_sizes.first().copied()).unwrap_or(0);
+            Guess { header, confidence: f64::from(score) / f64::from(total), evidence }
+        })
+        .collect()
+}
+
+/// If ROM sizes are possible for known boards of `mapper`
+fn fits(mapper: u16, prg_rom_size: usize, chr_rom_size: usize) -> bool {
+    lookup(mapper, 0).is_some_and(|info| {
+        prg_rom_size > 0
+            && prg_rom_size <= info.max_prg_rom_size as usize
+            && chr_rom_size <= info.max_chr_rom_size as usize
+            && (chr_rom_size > 0 || info.chr_ram)
+    })
+}
+
+fn clues(prg_rom: &[u8], chr_rom: &[u8]) -> Vec<Evidence> {
+    let mut clues = Vec::new();
+
+    let writes = rom_writes(prg_rom);
+    clues.push(if writes.is_empty() { Evidence::NoRomWrites } else { Evidence::RomWrites });
+
+    let serial = writes.iter().filter(|(offset, _)| is_serial_write(prg_rom, *offset)).count();
+    if serial >= MIN_PATTERNS {
+        clues.push(Evidence::SerialWrites);
+    }
+
+    let pairs = |first: fn(u16) -> bool, second: fn(u16) -> bool| {
+        writes
+            .windows(2)
+            .filter(|x| x[1].0 - x[0].0 <= PAIR_DISTANCE && first(x[0].1) && second(x[1].1))
+            .count()
+    };
+    if pairs(|x| x & 0xE001 == 0x8000, |x| x & 0xE001 == 0x8001) >= MIN_PATTERNS {
+        clues.push(Evidence::SelectDataPairs);
+    }
+    if pairs(|x| x & 0xE000 == 0x8000, |x| x & 0xE000 == 0xA000) >= MIN_PATTERNS {
+        clues.push(Evidence::CommandParameterPairs);
+    }
+
+    if prg_rom.len() <= 0x8000 {
+        clues.push(Evidence::SmallPrg);
+    }
+    if let Some(reset) = reset_vector(prg_rom) {
+        if reset >= 0xC000 {
+            clues.push(Evidence::ResetInLast16K);
+        }
+        if reset >= 0xE000 {
+            clues.push(Evidence::ResetInLast8K);
+        }
+    }
+    let banks = prg_rom.chunks_exact(0x8000);
+    if banks.len() > 1
+        && banks.map(reset_vector).collect::<Vec<_>>().windows(2).all(|x| x[0] == x[1])
+    {
+        clues.push(Evidence::ResetInEvery32K);
+    }
+
+    if chr_rom.is_empty() {
+        clues.push(Evidence::ChrRam);
+    } else if chr_rom.len() > 0x2000 {
+        clues.push(Evidence::ChrBanks);
+    }
+
+    clues
+}
+
+/// Offsets and target addresses of absolute store instructions which write to ROM area
+fn rom_writes(prg_rom: &[u8]) -> Vec<(usize, u16)> {
+    // STY abs, STA abs, STX abs, STA abs,Y and STA abs,X
+    const STORES: [u8; 5] = [0x8C, 0x8D, 0x8E, 0x99, 0x9D];
+
+    prg_rom
+        .windows(3)
+        .enumerate()
+        .filter(|(_, x)| STORES.contains(&x[0]))
+        .map(|(offset, x)| (offset, u16::from_le_bytes([x[1], x[2]])))
+        .filter(|(_, address)| *address >= 0x8000)
+        .collect()
+}
+
+fn is_serial_write(prg_rom: &[u8], offset: usize) -> bool {
+    // STA abs, LSR A, STA abs
+    prg_rom
+        .get(offset..offset + 7)
+        .is_some_and(|x| x[0] == 0x8D && x[3] == 0x4A && x[4] == 0x8D && x[1..3] == x[5..7])
+}
+
+fn reset_vector(prg_rom: &[u8]) -> Option<u16> {
+    let len = prg_rom.len();
+    let bytes = prg_rom.get(len.checked_sub(4)?..len - 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
@@ -0,0 +1,505 @@
+use {
+    super::super::{BankSize, NESFile},
+    std::ops::Range,
+};
+
+/// Bank registers of a mapper, which decides how ROM is mapped to CPU and PPU address space
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum BankState {
+    /// Mapper 0, no bank switching
+    Nrom,
+    /// Mapper 2, 16K PRG bank at $8000, last bank fixed at $C000
+    UxRom {
+        /// PRG bank select
+        prg: u8,
+    },
+    /// Mapper 3, 8K CHR bank
+    CnRom {
+        /// CHR bank select
+        chr: u8,
+    },
+    /// Mapper 7, 32K PRG bank
+    AxRom {
+        /// PRG bank select, bit 4 is single-screen select and ignored here
+        prg: u8,
+    },
+    /// Mapper 1, MMC1 internal registers after serial writes
+    Mmc1 {
+        /// Control register ($8000)
+        control: u8,
+        /// CHR bank 0 ($A000)
+        chr0: u8,
+        /// CHR bank 1 ($C000)
+        chr1: u8,
+        /// PRG bank ($E000)
+        prg: u8,
+    },
+    /// Mapper 4, MMC3 registers
+    Mmc3 {
+        /// Bank select register ($8000)
+        bank_select: u8,
+        /// Bank data registers R0 - R7 ($8001)
+        registers: [u8; 8],
+    },
+    /// Mapper 9, MMC2 registers
+    Mmc2 {
+        /// 8K PRG bank at $8000
+        prg: u8,
+        /// 4K CHR banks currently selected by latches
+        chr: [u8; 2],
+    },
+    /// Mapper 10, MMC4 registers
+    Mmc4 {
+        /// 16K PRG bank at $8000
+        prg: u8,
+        /// 4K CHR banks currently selected by latches
+        chr: [u8; 2],
+    },
+    /// Mapper 11 and 66, 32K PRG bank and 8K CHR bank
+    GxRom {
+        /// PRG bank select
+        prg: u8,
+        /// CHR bank select
+        chr: u8,
+    },
+    /// Mapper 69, Sunsoft FME-7 registers
+    Fme7 {
+        /// PRG banks at $8000, $A000 and $C000
+        prg: [u8; 3],
+        /// 1K CHR banks
+        chr: [u8; 8],
+    },
+    /// Mapper 5, MMC5 registers
+    Mmc5 {
+        /// PRG mode ($5100)
+        prg_mode: u8,
+        /// PRG bank registers ($5114 - $5117)
+        prg: [u8; 4],
+        /// CHR mode ($5101)
+        chr_mode: u8,
+        /// Sprite CHR bank registers ($5120 - $5127), including upper bits from $5130
+        chr: [u16; 8],
+    },
+    /// Mapper 75, VRC1 registers
+    Vrc1 {
+        /// PRG banks at $8000, $A000 and $C000
+        prg: [u8; 3],
+        /// 4K CHR banks, including high bits from $9000
+        chr: [u8; 2],
+    },
+    /// Mapper 21, 22, 23 and 25, VRC2 and VRC4 registers
+    Vrc2Vrc4 {
+        /// PRG banks selected by $8000 and $A000
+        prg: [u8; 2],
+        /// VRC4 PRG swap mode, swaps $8000 and $C000
+        prg_swap: bool,
+        /// 1K CHR banks
+        chr: [u16; 8],
+    },
+    /// Mapper 73, VRC3 registers
+    Vrc3 {
+        /// 16K PRG bank at $8000
+        prg: u8,
+    },
+    /// Mapper 24 and 26, VRC6 registers, only CHR mode 0 ($B003) is supported
+    Vrc6 {
+        /// 16K PRG bank at $8000
+        prg16: u8,
+        /// 8K PRG bank at $C000
+        prg8: u8,
+        /// 1K CHR banks
+        chr: [u8; 8],
+    },
+    /// Mapper 85, VRC7 registers
+    Vrc7 {
+        /// PRG banks at $8000, $A000 and $C000
+        prg: [u8; 3],
+        /// 1K CHR banks
+        chr: [u8; 8],
+    },
+}
+
+impl BankState {
+    /// Registers state at power on for `mapper`, `None` if the mapper is not supported.
+    ///
+    /// Registers with unspecified power on value are set to 0, except MMC1 control register,
+    /// which is set to fix the last PRG bank at $C000 as most boards guarantee.
+    #[must_use]
+    pub const fn power_on(mapper: u16) -> Option<Self> {
+        Some(match mapper {
+            0 => Self::Nrom,
+            1 => Self::Mmc1 { control: 0x0C, chr0: 0, chr1: 0, prg: 0 },
+            2 => Self::UxRom { prg: 0 },
+            3 => Self::CnRom { chr: 0 },
+            4 => Self::Mmc3 { bank_select: 0, registers: [0, 2, 4, 5, 6, 7, 0, 1] },
+            5 => Self::Mmc5 { prg_mode: 3, prg: [0xFF; 4], chr_mode: 0, chr: [0; 8] },
+            7 => Self::AxRom { prg: 0 },
+            9 => Self::Mmc2 { prg: 0, chr: [0; 2] },
+            10 => Self::Mmc4 { prg: 0, chr: [0; 2] },
+            11 | 66 => Self::GxRom { prg: 0, chr: 0 },
+            69 => Self::Fme7 { prg: [0, 1, 2], chr: [0; 8] },
+            21 | 22 | 23 | 25 => Self::Vrc2Vrc4 { prg: [0, 1], prg_swap: false, chr: [0; 8] },
+            24 | 26 => Self::Vrc6 { prg16: 0, prg8: 0, chr: [0; 8] },
+            73 => Self::Vrc3 { prg: 0 },
+            75 => Self::Vrc1 { prg: [0, 1, 2], chr: [0; 2] },
+            85 => Self::Vrc7 { prg: [0, 1, 2], chr: [0; 8] },
+            _ => return None,
+        })
+    }
+
+    /// PRG-ROM windows which no register can switch to another bank, with `prg_rom_size`.
+    ///
+    /// Windows which are only fixed in some modes, like MMC1 $C000, are not included.
+    #[must_use]
+    pub fn fixed_prg(&self, prg_rom_size: usize) -> Vec<Window> {
+        let mut map = Mapping::new(prg_rom_size, 0);
+        self.map(&mut map);
+        map.fixed
+    }
+}
+
+/// A window of CPU or PPU address space which is mapped to ROM
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Window {
+    /// Start address in CPU or PPU address space
+    pub address: u16,
+    /// Window size
+    pub size: BankSize,
+    /// Offset in PRG-ROM or CHR-ROM where the window starts
+    pub offset: usize,
+}
+
+impl Window {
+    /// Address range in CPU or PPU address space
+    #[must_use]
+    pub fn addresses(&self) -> Range<u32> {
+        let start = u32::from(self.address);
+        #[allow(clippy::cast_possible_truncation)] // bank size is at most 32K
+        let end = start + self.size.bytes() as u32;
+        start..end
+    }
+
+    /// Offset range in PRG-ROM or CHR-ROM
+    #[must_use]
+    pub const fn offsets(&self) -> Range<usize> {
+        self.offset..self.offset + self.size.bytes()
+    }
+
+    fn translate(&self, address: u16) -> Option<usize> {
+        let delta = address.checked_sub(self.address)? as usize;
+        if delta < self.size.bytes() {
+            Some(self.offset + delta)
+        } else {
+            None
+        }
+    }
+
+    fn untranslate(&self, offset: usize) -> Option<u16> {
+        if self.offsets().contains(&offset) {
+            #[allow(clippy::cast_possible_truncation)] // window is in 16 bit address space
+            Some(self.address + (offset - self.offset) as u16)
+        } else {
+            None
+        }
+    }
+}
+
+/// How PRG-ROM is mapped to CPU $8000 - $FFFF and CHR-ROM to PPU $0000 - $1FFF
+///
+/// Areas mapped to RAM or not mapped have no window.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct MemoryMap {
+    /// PRG-ROM windows in CPU address space, ordered by address
+    pub prg: Vec<Window>,
+    /// CHR-ROM windows in PPU address space, ordered by address, empty if CHR-ROM is empty
+    pub chr: Vec<Window>,
+}
+
+impl MemoryMap {
+    /// Compute windows by bank registers `state` and sizes of ROMs.
+    ///
+    /// Bank numbers larger than bank count are wrapped, as the high address lines are not
+    /// connected on real boards.
+    #[must_use]
+    pub fn new(state: &BankState, prg_rom_size: usize, chr_rom_size: usize) -> Self {
+        let mut map = Mapping::new(prg_rom_size, chr_rom_size);
+        state.map(&mut map);
+        Self { prg: map.prg, chr: map.chr }
+    }
+
+    /// Compute windows for `file` with bank registers `state`.
+    #[must_use]
+    pub fn for_file(file: &NESFile<'_>, state: &BankState) -> Self {
+        Self::new(state, file.prg_rom.len(), file.chr_rom.len())
+    }
+
+    /// Translate a CPU address to PRG-ROM offset, `None` if not mapped to PRG-ROM.
+    #[must_use]
+    pub fn cpu_to_prg_rom(&self, address: u16) -> Option<usize> {
+        self.prg.iter().find_map(|window| window.translate(address))
+    }
+
+    /// Translate a PRG-ROM offset to all CPU addresses it's mapped to.
+    #[must_use]
+    pub fn prg_rom_to_cpu(&self, offset: usize) -> Vec<u16> {
+        self.prg.iter().filter_map(|window| window.untranslate(offset)).collect()
+    }
+
+    /// Translate a PPU address to CHR-ROM offset, `None` if not mapped to CHR-ROM.
+    #[must_use]
+    pub fn ppu_to_chr_rom(&self, address: u16) -> Option<usize> {
+        self.chr.iter().find_map(|window| window.translate(address))
+    }
+
+    /// Translate a CHR-ROM offset to all PPU addresses it's mapped to.
+    #[must_use]
+    pub fn chr_rom_to_ppu(&self, offset: usize) -> Vec<u16> {
+        self.chr.iter().filter_map(|window| window.untranslate(offset)).collect()
+    }
+}
+
+/// Collect windows while bank state is mapping
+struct Mapping {
+    prg_rom_size: usize,
+    chr_rom_size: usize,
+    prg: Vec<Window>,
+    chr: Vec<Window>,
+    /// PRG-ROM windows which can't be switched
+    fixed: Vec<Window>,
+}
+
+impl Mapping {
+    const fn new(prg_rom_size: usize, chr_rom_size: usize) -> Self {
+        Self { prg_rom_size, chr_rom_size, prg: Vec::new(), chr: Vec::new(), fixed: Vec::new() }
+    }
+
+    /// Record PRG-ROM windows mapped by `f` as fixed
+    fn fixed(&mut self, f: impl FnOnce(&mut Self)) {
+        let start = self.prg.len();
+        f(self);
+        let windows = self.prg[start..].to_vec();
+        self.fixed.extend(windows);
+    }
+
+    const fn prg_count(&self, size: BankSize) -> usize {
+        self.prg_rom_size / size.bytes()
+    }
+
+    fn prg(&mut self, address: u16, size: BankSize, bank: usize) {
+        add_window(&mut self.prg, self.prg_rom_size, address, size, bank);
+    }
+
+    /// Map bank counted from the last one, 1 means the last bank
+    fn prg_last(&mut self, address: u16, size: BankSize, nth: usize) {
+        let count = self.prg_count(size);
+        self.prg(address, size, count.saturating_sub(nth));
+    }
+
+    fn chr(&mut self, address: u16, size: BankSize, bank: usize) {
+        add_window(&mut self.chr, self.chr_rom_size, address, size, bank);
+    }
+}
+
+fn add_window(
+    windows: &mut Vec<Window>, rom_size: usize, address: u16, size: BankSize, bank: usize,
+) {
+    let count = rom_size / size.bytes();
+    if count > 0 {
+        let offset = bank % count * size.bytes();
+        windows.push(Window { address, size, offset });
+    } else if let Some(rom_bank_size) = BankSize::from_bytes(rom_size) {
+        // ROM is smaller than the window, so it's mirrored to fill the window
+        #[allow(clippy::cast_possible_truncation)] // window is in 16 bit address space
+        for i in 0..size.bytes() / rom_size {
+            windows.push(Window {
+                address: address + (i * rom_size) as u16,
+                size: rom_bank_size,
+                offset: 0,
+            });
+        }
+    }
+}
+
+impl BankState {
+    #[allow(clippy::too_many_lines)] // one arm for each mapper
+    fn map(&self, map: &mut Mapping) {
+        use BankSize::{Size16K, Size1K, Size2K, Size32K, Size4K, Size8K};
+
+        match *self {
+            Self::Nrom => {
+                map.fixed(|map| map.prg(0x8000, Size32K, 0));
+                map.chr(0x0000, Size8K, 0);
+            }
+            Self::UxRom { prg } | Self::Vrc3 { prg } => {
+                map.prg(0x8000, Size16K, prg.into());
+                map.fixed(|map| map.prg_last(0xC000, Size16K, 1));
+                map.chr(0x0000, Size8K, 0);
+            }
+            Self::CnRom { chr } => {
+                map.fixed(|map| map.prg(0x8000, Size32K, 0));
+                map.chr(0x0000, Size8K, chr.into());
+            }
+            Self::AxRom { prg } => {
+                map.prg(0x8000, Size32K, usize::from(prg & 0x0F));
+                map.chr(0x0000, Size8K, 0);
+            }
+            Self::Mmc1 { control, chr0, chr1, prg } => {
+                // SUROM and SXROM use CHR bank bit 4 to select 256K PRG-ROM outer bank
+                let outer =
+                    if map.prg_rom_size > 256 * 1024 { usize::from(chr0 & 0x10) } else { 0 };
+                let bank = usize::from(prg & 0x0F) | outer;
+                match (control >> 2) & 0b11 {
+                    0 | 1 => map.prg(0x8000, Size32K, bank >> 1),
+                    2 => {
+                        map.prg(0x8000, Size16K, outer);
+                        map.prg(0xC000, Size16K, bank);
+                    }
+                    _ => {
+                        map.prg(0x8000, Size16K, bank);
+                        map.prg(0xC000, Size16K, outer | 0x0F);
+                    }
+                }
+                if control & 0x10 == 0 {
+                    map.chr(0x0000, Size8K, usize::from(chr0 >> 1));
+                } else {
+                    map.chr(0x0000, Size4K, chr0.into());
+                    map.chr(0x1000, Size4K, chr1.into());
+                }
+            }
+            Self::Mmc3 { bank_select, registers: r } => {
+                let r = r.map(usize::from);
+                if bank_select & 0x40 == 0 {
+                    map.prg(0x8000, Size8K, r[6]);
+                    map.prg(0xA000, Size8K, r[7]);
+                    map.prg_last(0xC000, Size8K, 2);
+                } else {
+                    map.prg_last(0x8000, Size8K, 2);
+                    map.prg(0xA000, Size8K, r[7]);
+                    map.prg(0xC000, Size8K, r[6]);
+                }
+                map.fixed(|map| map.prg_last(0xE000, Size8K, 1));
+
+                let (two_k, one_k) =
+                    if bank_select & 0x80 == 0 { (0, 0x1000) } else { (0x1000, 0) };
+                map.chr(two_k, Size2K, r[0] >> 1);
+                map.chr(two_k + 0x0800, Size2K, r[1] >> 1);
+                for (i, &bank) in r[2..6].iter().enumerate() {
+                    #[allow(clippy::cast_possible_truncation)] // i < 4
+                    map.chr(one_k + 0x0400 * i as u16, Size1K, bank);
+                }
+            }
+            Self::Mmc2 { prg, chr } => {
+                map.prg(0x8000, Size8K, prg.into());
+                map.fixed(|map| {
+                    map.prg_last(0xA000, Size8K, 3);
+                    map.prg_last(0xC000, Size8K, 2);
+                    map.prg_last(0xE000, Size8K, 1);
+                });
+                map.chr(0x0000, Size4K, chr[0].into());
+                map.chr(0x1000, Size4K, chr[1].into());
+            }
+            Self::Mmc4 { prg, chr } => {
+                map.prg(0x8000, Size16K, prg.into());
+                map.fixed(|map| map.prg_last(0xC000, Size16K, 1));
+                map.chr(0x0000, Size4K, chr[0].into());
+                map.chr(0x1000, Size4K, chr[1].into());
+            }
+            Self::GxRom { prg, chr } => {
+                map.prg(0x8000, Size32K, prg.into());
+                map.chr(0x0000, Size8K, chr.into());
+            }
+            Self::Mmc5 { prg_mode, prg, chr_mode, chr } => {
+                let rom = |value: u8| {
+                    if value & 0x80 == 0 {
+                        None
+                    } else {
+                        Some(usize::from(value & 0x7F))
+                    }
+                };
+                let last = usize::from(prg[3] & 0x7F);
+                let mut windows = Vec::with_capacity(4);
+                match prg_mode & 0b11 {
+                    0 => windows.push((0x8000, Size32K, Some(last >> 2))),
+                    1 => {
+                        windows.push((0x8000, Size16K, rom(prg[1]).map(|x| x >> 1)));
+                        windows.push((0xC000, Size16K, Some(last >> 1)));
+                    }
+                    2 => {
+                        windows.push((0x8000, Size16K, rom(prg[1]).map(|x| x >> 1)));
+                        windows.push((0xC000, Size8K, rom(prg[2])));
+                        windows.push((0xE000, Size8K, Some(last)));
+                    }
+                    _ => {
+                        windows.push((0x8000, Size8K, rom(prg[0])));
+                        windows.push((0xA000, Size8K, rom(prg[1])));
+                        windows.push((0xC000, Size8K, rom(prg[2])));
+                        windows.push((0xE000, Size8K, Some(last)));
+                    }
+                }
+                for (address, size, bank) in windows {
+                    if let Some(bank) = bank {
+                        map.prg(address, size, bank);
+                    }
+                }
+
+                let chr = chr.map(usize::from);
+                match chr_mode & 0b11 {
+                    0 => map.chr(0x0000, Size8K, chr[7]),
+                    1 => {
+                        map.chr(0x0000, Size4K, chr[3]);
+                        map.chr(0x1000, Size4K, chr[7]);
+                    }
+                    2 => {
+                        for (i, &bank) in [chr[1], chr[3], chr[5], chr[7]].iter().enumerate() {
+                            #[allow(clippy::cast_possible_truncation)] // i < 4
+                            map.chr(0x0800 * i as u16, Size2K, bank);
+                        }
+                    }
+                    _ => {
+                        for (i, &bank) in chr.iter().enumerate() {
+                            #[allow(clippy::cast_possible_truncation)] // i < 8
+                            map.chr(0x0400 * i as u16, Size1K, bank);
+                        }
+                    }
+                }
+            }
+            Self::Vrc1 { prg, chr } => {
+                map.prg(0x8000, Size8K, prg[0].into());
+                map.prg(0xA000, Size8K, prg[1].into());
+                map.prg(0xC000, Size8K, prg[2].into());
+                map.fixed(|map| map.prg_last(0xE000, Size8K, 1));
+                map.chr(0x0000, Size4K, chr[0].into());
+                map.chr(0x1000, Size4K, chr[1].into());
+            }
+            Self::Vrc2Vrc4 { prg, prg_swap, chr } => {
+                let (first, third) = if prg_swap { (0xC000, 0x8000) } else { (0x8000, 0xC000) };
+                map.prg(first, Size8K, prg[0].into());
+                map.prg(0xA000, Size8K, prg[1].into());
+                map.prg_last(third, Size8K, 2);
+                map.fixed(|map| map.prg_last(0xE000, Size8K, 1));
+                map.prg.sort_by_key(|window| window.address);
+                chr_1k(map, &chr.map(usize::from));
+            }
+            Self::Vrc6 { prg16, prg8, chr } => {
+                map.prg(0x8000, Size16K, prg16.into());
+                map.prg(0xC000, Size8K, prg8.into());
+                map.fixed(|map| map.prg_last(0xE000, Size8K, 1));
+                chr_1k(map, &chr.map(usize::from));
+            }
+            Self::Vrc7 { prg, chr } | Self::Fme7 { prg, chr } => {
+                map.prg(0x8000, Size8K, prg[0].into());
+                map.prg(0xA000, Size8K, prg[1].into());
+                map.prg(0xC000, Size8K, prg[2].into());
+                map.fixed(|map| map.prg_last(0xE000, Size8K, 1));
+                chr_1k(map, &chr.map(usize::from));
+            }
+        }
+    }
+}
+
+fn chr_1k(map: &mut Mapping, banks: &[usize; 8]) {
+    for (i, &bank) in banks.iter().enumerate() {
+        #[allow(clippy::cast_possible_truncation)] // i < 8
+        map.chr(0x0400 * i as u16, BankSize::Size1K, bank);
+    }
+}
@@ -0,0 +1,120 @@
+//! Knowledge about mappers, the cartridge hardware which maps ROM/RAM to CPU/PPU address space
+
+mod catalog;
+mod emulation;
+mod guess;
+mod memory;
+mod submapper;
+
+pub use {
+    emulation::{create, Mapper, NametableMirroring},
+    guess::{guess, Evidence, Guess},
+    memory::{BankState, MemoryMap, Window},
+    submapper::{
+        submapper, BusConflicts, Mmc1Variant, Mmc3Variant, Namco163Variant, Submapper,
+        SunsoftVariant, VrcVariant,
+    },
+};
+
+use super::BankSize;
+
+/// Expansion audio chips on cartridge
+#[allow(missing_docs)] // because the variant name is clear enough
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ExpansionAudio {
+    Vrc6,
+    Vrc7,
+    Mmc5,
+    Namco163,
+    Sunsoft5B,
+}
+
+/// How name table mirroring is controlled
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum MirroringControl {
+    /// Hardwired by board, as header declared
+    Fixed,
+    /// Switched between horizontal and vertical by mapper
+    Switchable,
+    /// Switched between two single-screen modes by mapper
+    SingleScreen,
+    /// Switched between horizontal, vertical and two single-screen modes by mapper
+    SwitchableWithSingleScreen,
+    /// Each name table can be mapped separately by mapper
+    Arbitrary,
+}
+
+/// Name table mirroring of a cartridge, combining header flags and mapper knowledge
+///
+/// Get it by [`Header::effective_mirroring`](../header/struct.Header.html#method.effective_mirroring).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum EffectiveMirroring {
+    /// Hardwired by board, never changes
+    Fixed(NametableMirroring),
+    /// Controlled by mapper registers at run time
+    MapperControlled(MirroringControl),
+}
+
+/// Information about a mapper, or a submapper of it
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct MapperInfo {
+    /// iNES mapper number
+    pub mapper: u16,
+    /// Submapper this entry is specific to, `None` for the general entry of the mapper
+    pub sub_mapper: Option<u8>,
+    /// Common name
+    pub name: &'static str,
+    /// Names of boards using this mapper
+    pub boards: &'static [&'static str],
+    /// Manufacturer of the mapper
+    pub manufacturer: &'static str,
+    /// Supported PRG-ROM bank sizes, the only one is the fixed window when not bank switched
+    pub prg_bank_sizes: &'static [BankSize],
+    /// Supported CHR bank sizes, the only one is the fixed window when not bank switched
+    pub chr_bank_sizes: &'static [BankSize],
+    /// Max PRG-ROM size in bytes of known boards
+    pub max_prg_rom_size: u32,
+    /// Max CHR-ROM size in bytes of known boards, 0 if only CHR-RAM is used
+    pub max_chr_rom_size: u32,
+    /// If there are boards using CHR-RAM
+    pub chr_ram: bool,
+    /// PRG-RAM sizes in bytes of known boards, the first one is the default, empty if no
+    /// PRG-RAM at all
+    pub prg_ram_sizes: &'static [u32],
+    /// If there are boards with battery backed memory
+    pub battery: bool,
+    /// If mapper can generate IRQ
+    pub irq: bool,
+    /// Expansion audio chip
+    pub expansion_audio: Option<ExpansionAudio>,
+    /// If boards have bus conflicts
+    pub bus_conflicts: bool,
+    /// How name table mirroring is controlled
+    pub mirroring: MirroringControl,
+    /// If there are boards with four-screen VRAM
+    pub four_screen: bool,
+}
+
+/// All mappers and submappers known by this library, ordered by mapper and submapper number.
+#[must_use]
+pub const fn catalog() -> &'static [MapperInfo] {
+    catalog::CATALOG
+}
+
+/// Look up information of a mapper.
+///
+/// Entry of the submapper is returned if exists, otherwise the general entry of the mapper.
+///
+/// # Examples
+///
+/// ```rust
+/// let info = dotnes::mapper::lookup(4, 1).unwrap();
+/// assert_eq!(info.name, "MMC6");
+/// ```
+#[must_use]
+pub fn lookup(mapper: u16, sub_mapper: u8) -> Option<&'static MapperInfo> {
+    let mut entries = catalog::CATALOG.iter().filter(|info| info.mapper == mapper);
+    let general = entries.clone().find(|info| info.sub_mapper.is_none());
+    entries.find(|info| info.sub_mapper == Some(sub_mapper)).or(general)
+}
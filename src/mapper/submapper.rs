@@ -0,0 +1,232 @@
+use super::super::header::Header;
+
+/// Typed submapper, interpreted by mapper number
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Submapper {
+    /// Mapper 1
+    Mmc1(Mmc1Variant),
+    /// Mapper 2, 3 and 7
+    Discrete(BusConflicts),
+    /// Mapper 4
+    Mmc3(Mmc3Variant),
+    /// Mapper 19
+    Namco163(Namco163Variant),
+    /// Mapper 21, 22, 23 and 25
+    Vrc(VrcVariant),
+    /// Mapper 69
+    Sunsoft(SunsoftVariant),
+    /// Submapper without known meaning for the mapper
+    Unknown(u8),
+}
+
+/// MMC1 board variants
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Mmc1Variant {
+    /// Normal MMC1 boards
+    Normal,
+    /// SUROM, 512K PRG-ROM (deprecated submapper)
+    Surom,
+    /// SOROM, 16K PRG-RAM (deprecated submapper)
+    Sorom,
+    /// MMC1A, PRG-RAM always enabled (deprecated submapper)
+    Mmc1A,
+    /// SXROM, 32K PRG-RAM (deprecated submapper)
+    Sxrom,
+    /// `SEROM`, `SHROM` and `SH1ROM`, 32K PRG-ROM which is not bank switched
+    FixedPrg,
+    /// Famicom Network System 2ME
+    Famicom2ME,
+}
+
+/// Bus conflicts of discrete logic mappers
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum BusConflicts {
+    /// Not specified, emulators should be compatible with both cases
+    Unspecified,
+    /// No bus conflicts
+    None,
+    /// Written value is bitwise AND-ed with the ROM byte at the written address
+    And,
+}
+
+/// MMC3 compatible chips
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Mmc3Variant {
+    /// MMC3C, the "new" IRQ behavior
+    Mmc3C,
+    /// MMC6, with 1K internal PRG-RAM
+    Mmc6,
+    /// Acclaim MC-ACC, IRQ clocked on falling edge of PPU A12
+    McAcc,
+    /// MMC3A and NEC-made MMC3, the "old" IRQ behavior
+    Mmc3A,
+}
+
+/// Namco 163 expansion sound variants
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Namco163Variant {
+    /// Expansion sound volume is not specified
+    Unspecified,
+    /// No expansion sound
+    NoExpansionSound,
+    /// Expansion sound mixed about 11.0 to 13.0 dB louder than 2A03 square channel
+    ExpansionSoundLow,
+    /// Expansion sound mixed about 16.0 to 17.0 dB louder than 2A03 square channel
+    ExpansionSoundMedium,
+    /// Expansion sound mixed about 18.0 to 19.5 dB louder than 2A03 square channel
+    ExpansionSoundHigh,
+}
+
+/// Konami VRC2 and VRC4 variants, which differ by CPU address lines used as register select
+#[allow(missing_docs)] // because the variant name is clear enough
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum VrcVariant {
+    Vrc2a,
+    Vrc2b,
+    Vrc2c,
+    Vrc4a,
+    Vrc4b,
+    Vrc4c,
+    Vrc4d,
+    Vrc4e,
+    Vrc4f,
+    /// Mapper 21 without submapper
+    Vrc4aOrVrc4c,
+    /// Mapper 23 without submapper
+    Vrc2bOrVrc4eOrVrc4f,
+    /// Mapper 25 without submapper
+    Vrc2cOrVrc4bOrVrc4d,
+}
+
+impl VrcVariant {
+    /// If the chip is VRC4, `None` when ambiguous.
+    #[must_use]
+    pub const fn is_vrc4(self) -> Option<bool> {
+        match self {
+            Self::Vrc2a | Self::Vrc2b | Self::Vrc2c => Some(false),
+            Self::Vrc4a | Self::Vrc4b | Self::Vrc4c | Self::Vrc4d | Self::Vrc4e | Self::Vrc4f => {
+                Some(true)
+            }
+            Self::Vrc4aOrVrc4c => Some(true),
+            Self::Vrc2bOrVrc4eOrVrc4f | Self::Vrc2cOrVrc4bOrVrc4d => None,
+        }
+    }
+
+    /// CPU address line numbers connected to chip's A0 and A1 pins.
+    ///
+    /// Ambiguous variants return all possible wirings, emulators usually combine them.
+    #[must_use]
+    pub const fn address_lines(self) -> &'static [(u8, u8)] {
+        match self {
+            Self::Vrc2a | Self::Vrc2c | Self::Vrc4b => &[(1, 0)],
+            Self::Vrc2b | Self::Vrc4f => &[(0, 1)],
+            Self::Vrc4a => &[(1, 2)],
+            Self::Vrc4c => &[(6, 7)],
+            Self::Vrc4d => &[(3, 2)],
+            Self::Vrc4e => &[(2, 3)],
+            Self::Vrc4aOrVrc4c => &[(1, 2), (6, 7)],
+            Self::Vrc2bOrVrc4eOrVrc4f => &[(0, 1), (2, 3)],
+            Self::Vrc2cOrVrc4bOrVrc4d => &[(1, 0), (3, 2)],
+        }
+    }
+}
+
+/// Sunsoft FME-7 family chips
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SunsoftVariant {
+    /// FME-7, 5A or 5B, NES 2.0 does not distinguish them.
+    ///
+    /// Only 5B has expansion audio, but FME-7 games never write to its registers, so it's safe
+    /// to always emulate it.
+    Unspecified,
+}
+
+/// Interpret submapper number of `header` by its mapper.
+///
+/// # Examples
+///
+/// ```rust
+/// use dotnes::mapper::{Mmc3Variant, Submapper};
+///
+/// let mut data = std::fs::read("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+/// data[6] |= 0x40; // mapper 4
+/// data[7] |= 0x08; // NES 2.0
+/// data[8] = 0x10; // submapper 1
+/// let nes = dotnes::parse(&data).unwrap();
+/// assert_eq!(dotnes::mapper::submapper(&nes.header), Submapper::Mmc3(Mmc3Variant::Mmc6));
+/// ```
+#[must_use]
+pub fn submapper(header: &Header) -> Submapper {
+    let sub = header.sub_mapper;
+    let typed = match header.mapper {
+        1 => mmc1(sub).map(Submapper::Mmc1),
+        2 | 3 | 7 => bus_conflicts(sub).map(Submapper::Discrete),
+        4 => mmc3(sub).map(Submapper::Mmc3),
+        19 => namco163(sub).map(Submapper::Namco163),
+        21 | 22 | 23 | 25 => vrc(header.mapper, sub).map(Submapper::Vrc),
+        69 if sub == 0 => Some(Submapper::Sunsoft(SunsoftVariant::Unspecified)),
+        _ => None,
+    };
+    typed.unwrap_or(Submapper::Unknown(sub))
+}
+
+const fn mmc1(sub: u8) -> Option<Mmc1Variant> {
+    Some(match sub {
+        0 => Mmc1Variant::Normal,
+        1 => Mmc1Variant::Surom,
+        2 => Mmc1Variant::Sorom,
+        3 => Mmc1Variant::Mmc1A,
+        4 => Mmc1Variant::Sxrom,
+        5 => Mmc1Variant::FixedPrg,
+        6 => Mmc1Variant::Famicom2ME,
+        _ => return None,
+    })
+}
+
+const fn bus_conflicts(sub: u8) -> Option<BusConflicts> {
+    Some(match sub {
+        0 => BusConflicts::Unspecified,
+        1 => BusConflicts::None,
+        2 => BusConflicts::And,
+        _ => return None,
+    })
+}
+
+const fn mmc3(sub: u8) -> Option<Mmc3Variant> {
+    Some(match sub {
+        0 => Mmc3Variant::Mmc3C,
+        1 => Mmc3Variant::Mmc6,
+        3 => Mmc3Variant::McAcc,
+        4 => Mmc3Variant::Mmc3A,
+        _ => return None,
+    })
+}
+
+const fn namco163(sub: u8) -> Option<Namco163Variant> {
+    Some(match sub {
+        0 => Namco163Variant::Unspecified,
+        2 => Namco163Variant::NoExpansionSound,
+        3 => Namco163Variant::ExpansionSoundLow,
+        4 => Namco163Variant::ExpansionSoundMedium,
+        5 => Namco163Variant::ExpansionSoundHigh,
+        _ => return None,
+    })
+}
+
+const fn vrc(mapper: u16, sub: u8) -> Option<VrcVariant> {
+    Some(match (mapper, sub) {
+        (21, 0) => VrcVariant::Vrc4aOrVrc4c,
+        (21, 1) => VrcVariant::Vrc4a,
+        (21, 2) => VrcVariant::Vrc4c,
+        (22, 0) => VrcVariant::Vrc2a,
+        (23, 0) => VrcVariant::Vrc2bOrVrc4eOrVrc4f,
+        (23, 1) => VrcVariant::Vrc4f,
+        (23, 2) => VrcVariant::Vrc4e,
+        (23, 3) => VrcVariant::Vrc2b,
+        (25, 0) => VrcVariant::Vrc2cOrVrc4bOrVrc4d,
+        (25, 1) => VrcVariant::Vrc4b,
+        (25, 2) => VrcVariant::Vrc4d,
+        (25, 3) => VrcVariant::Vrc2c,
+        _ => return None,
+    })
+}
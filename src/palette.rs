@@ -0,0 +1,429 @@
+//! NES master palettes, which map 6-bit PPU color indices to RGB colors.
+//!
+//! Composite video PPUs (2C02, 2C07 and Dendy) have no exact RGB colors. The 2C02 table is the
+//! commonly used one, the 2C07 and Dendy tables are decoded from a model of their PAL signal,
+//! which rotates hues by 15 degrees, and Dendy clones lose some saturation. Color emphasis also
+//! differs by timing, see [`Emphasis`](enum.Emphasis.html). Load a `.pal` file for other colors.
+//!
+//! RGB PPUs of Vs. System and PlayChoice-10 output the colors in their tables, which are written
+//! here in the 3 bits per channel notation.
+
+use {
+    crate::header::{ConsoleType, Header, Timing, VsPPUType},
+    std::{error::Error, fmt},
+};
+
+/// A color in red, green and blue
+pub type Rgb = [u8; 3];
+
+/// Colors of the NTSC 2C02 PPU
+#[rustfmt::skip]
+const NTSC_COLORS: [Rgb; 64] = [
+    [0x54, 0x54, 0x54], [0x00, 0x1E, 0x74], [0x08, 0x10, 0x90], [0x30, 0x00, 0x88],
+    [0x44, 0x00, 0x64], [0x5C, 0x00, 0x30], [0x54, 0x04, 0x00], [0x3C, 0x18, 0x00],
+    [0x20, 0x2A, 0x00], [0x08, 0x3A, 0x00], [0x00, 0x40, 0x00], [0x00, 0x3C, 0x00],
+    [0x00, 0x32, 0x3C], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00],
+    [0x98, 0x96, 0x98], [0x08, 0x4C, 0xC4], [0x30, 0x32, 0xEC], [0x5C, 0x1E, 0xE4],
+    [0x88, 0x14, 0xB0], [0xA0, 0x14, 0x64], [0x98, 0x22, 0x20], [0x78, 0x3C, 0x00],
+    [0x54, 0x5A, 0x00], [0x28, 0x72, 0x00], [0x08, 0x7C, 0x00], [0x00, 0x76, 0x28],
+    [0x00, 0x66, 0x78], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00],
+    [0xEC, 0xEE, 0xEC], [0x4C, 0x9A, 0xEC], [0x78, 0x7C, 0xEC], [0xB0, 0x62, 0xEC],
+    [0xE4, 0x54, 0xEC], [0xEC, 0x58, 0xB4], [0xEC, 0x6A, 0x64], [0xD4, 0x88, 0x20],
+    [0xA0, 0xAA, 0x00], [0x74, 0xC4, 0x00], [0x4C, 0xD0, 0x20], [0x38, 0xCC, 0x6C],
+    [0x38, 0xB4, 0xCC], [0x3C, 0x3C, 0x3C], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00],
+    [0xEC, 0xEE, 0xEC], [0xA8, 0xCC, 0xEC], [0xBC, 0xBC, 0xEC], [0xD4, 0xB2, 0xEC],
+    [0xEC, 0xAE, 0xEC], [0xEC, 0xAE, 0xD4], [0xEC, 0xB4, 0xB0], [0xE4, 0xC4, 0x90],
+    [0xCC, 0xD2, 0x78], [0xB4, 0xDE, 0x78], [0xA8, 0xE2, 0x90], [0x98, 0xE2, 0xB4],
+    [0xA0, 0xD6, 0xE4], [0xA0, 0xA2, 0xA0], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00],
+];
+
+/// Colors of the PAL 2C07 PPU
+#[rustfmt::skip]
+const PAL_COLORS: [Rgb; 64] = [
+    [0x4B, 0x4B, 0x4B], [0x00, 0x23, 0x57], [0x02, 0x11, 0x82], [0x1B, 0x03, 0x8D],
+    [0x39, 0x00, 0x76], [0x4F, 0x00, 0x45], [0x55, 0x00, 0x0E], [0x49, 0x0B, 0x00],
+    [0x2F, 0x1C, 0x00], [0x11, 0x2D, 0x00], [0x00, 0x39, 0x00], [0x00, 0x3B, 0x00],
+    [0x00, 0x33, 0x1F], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00],
+    [0x92, 0x92, 0x92], [0x01, 0x54, 0xA4], [0x1C, 0x37, 0xE4], [0x47, 0x1E, 0xEC],
+    [0x76, 0x10, 0xD3], [0x98, 0x0E, 0x88], [0xA1, 0x18, 0x32], [0x8F, 0x2C, 0x00],
+    [0x67, 0x48, 0x00], [0x38, 0x64, 0x00], [0x11, 0x76, 0x00], [0x00, 0x79, 0x05],
+    [0x00, 0x6C, 0x4E], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00],
+    [0xEC, 0xEC, 0xEC], [0x3C, 0xA5, 0xEC], [0x62, 0x83, 0xEC], [0x97, 0x65, 0xEC],
+    [0xCC, 0x53, 0xEC], [0xEC, 0x4F, 0xE1], [0xEC, 0x5C, 0x7D], [0xE8, 0x76, 0x29],
+    [0xBB, 0x98, 0x00], [0x84, 0xB8, 0x00], [0x53, 0xCC, 0x07], [0x34, 0xD0, 0x42],
+    [0x2C, 0xC1, 0x9E], [0x39, 0x39, 0x39], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00],
+    [0xEC, 0xEC, 0xEC], [0xA1, 0xCF, 0xEC], [0xB2, 0xC1, 0xEC], [0xC9, 0xB4, 0xEC],
+    [0xDF, 0xAB, 0xEC], [0xEC, 0xAA, 0xE7], [0xEC, 0xB0, 0xBE], [0xEA, 0xBB, 0x98],
+    [0xD8, 0xC9, 0x7F], [0xC1, 0xD7, 0x78], [0xAB, 0xDF, 0x85], [0x9D, 0xE1, 0xA4],
+    [0x99, 0xDB, 0xCC], [0x9F, 0x9F, 0x9F], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00],
+];
+
+/// Colors of the Dendy PPU, UA6538 and other clones
+#[rustfmt::skip]
+const DENDY_COLORS: [Rgb; 64] = [
+    [0x4B, 0x4B, 0x4B], [0x00, 0x21, 0x50], [0x03, 0x11, 0x76], [0x1B, 0x05, 0x80],
+    [0x35, 0x00, 0x6C], [0x49, 0x00, 0x40], [0x4E, 0x02, 0x0F], [0x44, 0x0C, 0x00],
+    [0x2D, 0x1B, 0x00], [0x12, 0x2B, 0x00], [0x00, 0x35, 0x00], [0x00, 0x37, 0x00],
+    [0x00, 0x30, 0x1E], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00],
+    [0x92, 0x92, 0x92], [0x06, 0x52, 0x99], [0x1F, 0x38, 0xD2], [0x47, 0x22, 0xE2],
+    [0x70, 0x15, 0xC3], [0x8E, 0x12, 0x80], [0x97, 0x1B, 0x33], [0x86, 0x2E, 0x00],
+    [0x63, 0x48, 0x00], [0x39, 0x60, 0x00], [0x15, 0x70, 0x00], [0x01, 0x73, 0x09],
+    [0x00, 0x68, 0x4C], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00],
+    [0xEC, 0xEC, 0xEC], [0x43, 0xA2, 0xEC], [0x66, 0x84, 0xEC], [0x96, 0x69, 0xEC],
+    [0xC6, 0x58, 0xEC], [0xE8, 0x55, 0xD8], [0xEC, 0x61, 0x7F], [0xDF, 0x79, 0x32],
+    [0xB7, 0x97, 0x08], [0x85, 0xB3, 0x00], [0x58, 0xC6, 0x12], [0x3C, 0xC9, 0x49],
+    [0x35, 0xBC, 0x9C], [0x39, 0x39, 0x39], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00],
+    [0xEC, 0xEC, 0xEC], [0xA4, 0xCE, 0xEC], [0xB4, 0xC1, 0xEC], [0xC8, 0xB5, 0xEC],
+    [0xDC, 0xAE, 0xEC], [0xEA, 0xAD, 0xE4], [0xEC, 0xB2, 0xBF], [0xE7, 0xBC, 0x9C],
+    [0xD6, 0xC9, 0x85], [0xC1, 0xD5, 0x7F], [0xAE, 0xDD, 0x8B], [0xA1, 0xDE, 0xA7],
+    [0x9D, 0xD8, 0xCB], [0x9F, 0x9F, 0x9F], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00],
+];
+
+/// Colors of the RGB PPU RP2C03, also used by RC2C03 and RC2C05
+#[rustfmt::skip]
+const RP2C03_COLORS: [u16; 64] = [
+    0o333, 0o014, 0o006, 0o326, 0o403, 0o503, 0o510, 0o420,
+    0o320, 0o120, 0o031, 0o040, 0o022, 0o000, 0o000, 0o000,
+    0o555, 0o036, 0o027, 0o407, 0o507, 0o704, 0o700, 0o630,
+    0o430, 0o140, 0o040, 0o053, 0o044, 0o000, 0o000, 0o000,
+    0o777, 0o357, 0o447, 0o637, 0o707, 0o737, 0o740, 0o750,
+    0o660, 0o360, 0o070, 0o276, 0o077, 0o000, 0o000, 0o000,
+    0o777, 0o567, 0o657, 0o757, 0o747, 0o755, 0o764, 0o772,
+    0o773, 0o572, 0o473, 0o276, 0o467, 0o000, 0o000, 0o000,
+];
+
+/// Colors of the RGB PPU RP2C04-0001, which has a scrambled order
+#[rustfmt::skip]
+const RP2C04_0001_COLORS: [u16; 64] = [
+    0o755, 0o637, 0o700, 0o447, 0o044, 0o120, 0o222, 0o704,
+    0o777, 0o333, 0o750, 0o503, 0o403, 0o660, 0o320, 0o777,
+    0o357, 0o653, 0o310, 0o360, 0o467, 0o657, 0o764, 0o027,
+    0o760, 0o276, 0o000, 0o200, 0o666, 0o444, 0o707, 0o014,
+    0o003, 0o567, 0o757, 0o070, 0o077, 0o022, 0o053, 0o507,
+    0o000, 0o420, 0o747, 0o510, 0o407, 0o006, 0o740, 0o000,
+    0o000, 0o140, 0o555, 0o031, 0o572, 0o326, 0o770, 0o630,
+    0o020, 0o036, 0o040, 0o111, 0o773, 0o737, 0o430, 0o473,
+];
+
+/// Colors of the RGB PPU RP2C04-0002
+#[rustfmt::skip]
+const RP2C04_0002_COLORS: [u16; 64] = [
+    0o000, 0o750, 0o430, 0o572, 0o473, 0o737, 0o044, 0o567,
+    0o700, 0o407, 0o773, 0o747, 0o777, 0o637, 0o467, 0o040,
+    0o020, 0o357, 0o510, 0o666, 0o053, 0o360, 0o200, 0o447,
+    0o222, 0o707, 0o003, 0o276, 0o657, 0o320, 0o000, 0o326,
+    0o403, 0o764, 0o740, 0o757, 0o036, 0o310, 0o555, 0o006,
+    0o507, 0o760, 0o333, 0o120, 0o027, 0o000, 0o660, 0o777,
+    0o653, 0o111, 0o070, 0o630, 0o022, 0o014, 0o704, 0o140,
+    0o000, 0o077, 0o420, 0o770, 0o755, 0o503, 0o031, 0o444,
+];
+
+/// Colors of the RGB PPU RP2C04-0003
+#[rustfmt::skip]
+const RP2C04_0003_COLORS: [u16; 64] = [
+    0o507, 0o737, 0o473, 0o555, 0o040, 0o777, 0o567, 0o120,
+    0o014, 0o000, 0o764, 0o320, 0o704, 0o666, 0o653, 0o467,
+    0o447, 0o044, 0o503, 0o027, 0o140, 0o430, 0o630, 0o053,
+    0o333, 0o326, 0o000, 0o006, 0o700, 0o510, 0o747, 0o755,
+    0o637, 0o020, 0o003, 0o770, 0o111, 0o750, 0o740, 0o777,
+    0o360, 0o403, 0o357, 0o707, 0o036, 0o444, 0o000, 0o310,
+    0o077, 0o200, 0o572, 0o757, 0o420, 0o070, 0o660, 0o222,
+    0o031, 0o000, 0o657, 0o773, 0o407, 0o276, 0o760, 0o022,
+];
+
+/// Colors of the RGB PPU RP2C04-0004
+#[rustfmt::skip]
+const RP2C04_0004_COLORS: [u16; 64] = [
+    0o430, 0o326, 0o044, 0o660, 0o000, 0o755, 0o014, 0o630,
+    0o555, 0o310, 0o070, 0o003, 0o764, 0o770, 0o040, 0o572,
+    0o737, 0o200, 0o027, 0o747, 0o000, 0o222, 0o510, 0o740,
+    0o653, 0o053, 0o447, 0o140, 0o403, 0o000, 0o473, 0o357,
+    0o503, 0o031, 0o420, 0o006, 0o407, 0o507, 0o333, 0o704,
+    0o022, 0o666, 0o036, 0o020, 0o111, 0o773, 0o444, 0o707,
+    0o757, 0o777, 0o320, 0o700, 0o760, 0o276, 0o777, 0o467,
+    0o000, 0o750, 0o637, 0o567, 0o360, 0o657, 0o077, 0o120,
+];
+
+/// Convert colors in 3 bits per channel notation, like `0o753`
+const fn rgb_333(table: &[u16; 64]) -> [Rgb; 64] {
+    let mut colors = [[0; 3]; 64];
+    let mut i = 0;
+    while i < 64 {
+        let mut channel = 0;
+        while channel < 3 {
+            let level = (table[i] >> (6 - channel * 3)) & 7;
+            // scale 0..=7 to 0..=255, rounded
+            #[allow(clippy::cast_possible_truncation)] // the result <= 255
+            let value = ((level * 255 + 3) / 7) as u8;
+            colors[i][channel] = value;
+            channel += 1;
+        }
+        i += 1;
+    }
+    colors
+}
+
+/// `.pal` file can't be parsed
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PaletteError {
+    /// File size is not a supported size
+    InvalidSize {
+        /// File size in bytes
+        size: usize,
+    },
+}
+
+impl fmt::Display for PaletteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSize { size } => {
+                write!(f, "palette file size {size} is not 192 or 1536 bytes")
+            }
+        }
+    }
+}
+
+impl Error for PaletteError {}
+
+/// Bytes of a `.pal` file with 64 colors
+const PAL_BYTES: usize = 64 * 3;
+
+/// Bytes of a `.pal` file with 64 colors for each of the 8 emphasis combinations
+const EMPHASIS_PAL_BYTES: usize = PAL_BYTES * 8;
+
+fn parse_colors(data: &[u8]) -> [Rgb; 64] {
+    let mut colors = [[0; 3]; 64];
+    for (color, bytes) in colors.iter_mut().zip(data.chunks_exact(3)) {
+        color.copy_from_slice(bytes);
+    }
+    colors
+}
+
+/// A master palette, RGB colors of the 64 color indices PPU can output
+///
+/// # Examples
+///
+/// ```rust
+/// use dotnes::palette::Palette;
+///
+/// let colors = Palette::NTSC.select([0x0F, 0x00, 0x10, 0x30]);
+/// assert_eq!(colors[0], [0, 0, 0]);
+/// assert_eq!(colors[3], [236, 238, 236]);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Palette {
+    /// Colors by index
+    pub colors: [Rgb; 64],
+}
+
+impl Palette {
+    /// Colors of the NTSC 2C02 PPU, which is used by most consoles
+    pub const NTSC: Self = Self { colors: NTSC_COLORS };
+    /// Colors of the PAL 2C07 PPU
+    pub const PAL: Self = Self { colors: PAL_COLORS };
+    /// Colors of the Dendy PPU, used by famiclones with PAL output
+    pub const DENDY: Self = Self { colors: DENDY_COLORS };
+    /// Colors of the RGB PPU RP2C03, also used by RC2C03 and RC2C05 PPUs
+    pub const RP2C03: Self = Self { colors: rgb_333(&RP2C03_COLORS) };
+    /// Colors of the RGB PPU RP2C04-0001
+    pub const RP2C04_0001: Self = Self { colors: rgb_333(&RP2C04_0001_COLORS) };
+    /// Colors of the RGB PPU RP2C04-0002
+    pub const RP2C04_0002: Self = Self { colors: rgb_333(&RP2C04_0002_COLORS) };
+    /// Colors of the RGB PPU RP2C04-0003
+    pub const RP2C04_0003: Self = Self { colors: rgb_333(&RP2C04_0003_COLORS) };
+    /// Colors of the RGB PPU RP2C04-0004
+    pub const RP2C04_0004: Self = Self { colors: rgb_333(&RP2C04_0004_COLORS) };
+
+    /// Colors of a Vs. System PPU, [`RP2C03`](#associatedconstant.RP2C03) for a reserved type.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dotnes::{header::VsPPUType, palette::Palette};
+    ///
+    /// assert_eq!(Palette::for_vs_ppu(VsPPUType::RC2C0503), Palette::RP2C03);
+    /// assert_eq!(Palette::for_vs_ppu(VsPPUType::RP2C040002).color(0x00), [0, 0, 0]);
+    /// ```
+    #[must_use]
+    pub const fn for_vs_ppu(ppu: VsPPUType) -> Self {
+        match ppu {
+            VsPPUType::RP2C040001 => Self::RP2C04_0001,
+            VsPPUType::RP2C040002 => Self::RP2C04_0002,
+            VsPPUType::RP2C040003 => Self::RP2C04_0003,
+            VsPPUType::RP2C040004 => Self::RP2C04_0004,
+            _ => Self::RP2C03,
+        }
+    }
+
+    /// Colors of the PPU `header` declares, by console type and timing. Multiple region games
+    /// get [`NTSC`](#associatedconstant.NTSC).
+    #[must_use]
+    pub const fn for_header(header: &Header) -> Self {
+        match (header.console_type, header.timing) {
+            (ConsoleType::Vs(info), _) => Self::for_vs_ppu(info.ppu_type),
+            (ConsoleType::PC10, _) => Self::RP2C03,
+            (_, Timing::PAL) => Self::PAL,
+            (_, Timing::Dendy) => Self::DENDY,
+            _ => Self::NTSC,
+        }
+    }
+
+    /// Parse a `.pal` file, which is 64 colors of 3 bytes each. For a file with emphasis
+    /// colors, only the first 64 colors are used, see
+    /// [`EmphasisPalette`](struct.EmphasisPalette.html).
+    ///
+    /// # Errors
+    ///
+    /// When size of `data` is not 192 or 1536 bytes, return
+    /// Err([`PaletteError::InvalidSize`](enum.PaletteError.html#variant.InvalidSize)).
+    pub fn from_pal(data: &[u8]) -> Result<Self, PaletteError> {
+        if data.len() != PAL_BYTES && data.len() != EMPHASIS_PAL_BYTES {
+            return Err(PaletteError::InvalidSize { size: data.len() });
+        }
+        Ok(Self { colors: parse_colors(data) })
+    }
+
+    /// Write as a 192 bytes `.pal` file.
+    #[must_use]
+    pub fn to_pal(&self) -> Vec<u8> {
+        self.colors.concat()
+    }
+
+    /// Color of PPU color `index`, only the low 6 bits are used like PPU does.
+    #[must_use]
+    pub const fn color(&self, index: u8) -> Rgb {
+        self.colors[(index & 0x3F) as usize]
+    }
+
+    /// Colors of a 4-color palette in PPU palette RAM, like `[0x0F, 0x00, 0x10, 0x30]`.
+    #[must_use]
+    pub const fn select(&self, indices: [u8; 4]) -> [Rgb; 4] {
+        [
+            self.color(indices[0]),
+            self.color(indices[1]),
+            self.color(indices[2]),
+            self.color(indices[3]),
+        ]
+    }
+
+    /// Colors with each combination of emphasis bits, applied as `emphasis` does.
+    #[must_use]
+    pub fn emphasize(&self, emphasis: Emphasis) -> EmphasisPalette {
+        let mut colors = [self.colors; 8];
+        for (bits, palette) in (0..).zip(colors.iter_mut()) {
+            for color in palette.iter_mut() {
+                *color = emphasis.apply(*color, bits);
+            }
+        }
+        EmphasisPalette { colors }
+    }
+}
+
+/// How a PPU applies the color emphasis bits 5 to 7 of PPUMASK register
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Emphasis {
+    /// Darken the other channels, bits are red, green and blue, like the 2C02
+    Darken,
+    /// Darken the other channels, bits are green, red and blue, like the 2C07 and Dendy
+    DarkenSwapped,
+    /// Set the channel to full, bits are red, green and blue, like the RGB PPUs
+    Saturate,
+}
+
+impl Emphasis {
+    /// How the PPU `header` declares applies emphasis, by console type and timing.
+    #[must_use]
+    pub const fn for_header(header: &Header) -> Self {
+        match (header.console_type, header.timing) {
+            (ConsoleType::Vs(_) | ConsoleType::PC10, _) => Self::Saturate,
+            (_, Timing::PAL | Timing::Dendy) => Self::DarkenSwapped,
+            _ => Self::Darken,
+        }
+    }
+
+    /// Apply emphasis `bits`, which is PPUMASK bits 5 to 7 shifted to bits 0 to 2.
+    fn apply(self, color: Rgb, bits: u8) -> Rgb {
+        let bits = match self {
+            Self::DarkenSwapped => bits & 0b100 | (bits & 1) << 1 | (bits >> 1) & 1,
+            Self::Darken | Self::Saturate => bits,
+        };
+        let mut result = color;
+        for (channel, value) in (0..).zip(result.iter_mut()) {
+            let own = bits & (1 << channel) != 0;
+            if self == Self::Saturate {
+                if own {
+                    *value = 0xFF;
+                }
+            } else {
+                let others = (bits & !(1 << channel)).count_ones();
+                for _ in 0..others {
+                    // about 0.816, the attenuation measured on 2C02
+                    #[allow(clippy::cast_possible_truncation)] // smaller than the u8 value
+                    let darker = (u16::from(*value) * 209 / 256) as u8;
+                    *value = darker;
+                }
+            }
+        }
+        result
+    }
+}
+
+/// A master palette with colors of all 8 emphasis bit combinations
+///
+/// # Examples
+///
+/// ```rust
+/// use dotnes::palette::{Emphasis, EmphasisPalette, Palette};
+///
+/// let palette = Palette::NTSC.emphasize(Emphasis::Darken);
+/// assert_eq!(palette.color(0x00, 0x30), [236, 238, 236]);
+/// // red emphasis darkens green and blue
+/// assert_eq!(palette.color(0x20, 0x30), [236, 194, 192]);
+///
+/// let pal = palette.to_pal();
+/// assert_eq!(pal.len(), 1536);
+/// assert_eq!(EmphasisPalette::from_pal(&pal).unwrap(), palette);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct EmphasisPalette {
+    /// Colors of 64 color indices, for emphasis bits 0 to 7, which are PPUMASK bits 5 to 7
+    pub colors: [[Rgb; 64]; 8],
+}
+
+impl EmphasisPalette {
+    /// Parse a 1536 bytes `.pal` file, 64 colors for each emphasis bit combination.
+    ///
+    /// # Errors
+    ///
+    /// When size of `data` is not 1536 bytes, return
+    /// Err([`PaletteError::InvalidSize`](enum.PaletteError.html#variant.InvalidSize)).
+    pub fn from_pal(data: &[u8]) -> Result<Self, PaletteError> {
+        if data.len() != EMPHASIS_PAL_BYTES {
+            return Err(PaletteError::InvalidSize { size: data.len() });
+        }
+        let mut colors = [[[0; 3]; 64]; 8];
+        for (palette, data) in colors.iter_mut().zip(data.chunks_exact(PAL_BYTES)) {
+            *palette = parse_colors(data);
+        }
+        Ok(Self { colors })
+    }
+
+    /// Write as a 1536 bytes `.pal` file.
+    #[must_use]
+    pub fn to_pal(&self) -> Vec<u8> {
+        self.colors.iter().flat_map(|x| x.concat()).collect()
+    }
+
+    /// Colors without emphasis.
+    #[must_use]
+    pub const fn base(&self) -> Palette {
+        Palette { colors: self.colors[0] }
+    }
+
+    /// Color of PPU color `index` when PPUMASK register is `mask`, other bits of `mask` than
+    /// emphasis bits are ignored.
+    #[must_use]
+    pub const fn color(&self, mask: u8, index: u8) -> Rgb {
+        self.colors[(mask >> 5) as usize][(index & 0x3F) as usize]
+    }
+}
@@ -0,0 +1,245 @@
+//! Parse NES file from a stream, without loading the whole file into memory
+
+use {
+    super::{
+        header::parser::parse_header, BankSize, Header, Layout, ParseError, Section, HEADER_SIZE,
+    },
+    std::{
+        convert::TryFrom,
+        error::Error,
+        fmt,
+        io::{self, Read, Seek, SeekFrom},
+        ops::Range,
+    },
+};
+
+/// Failed reason of [`parse_reader`](fn.parse_reader.html)
+#[derive(Debug)]
+pub enum ReadError {
+    /// Error happened when reading from or seeking in the underlying stream
+    Io(io::Error),
+    /// Stream content is not valid NES format data
+    Parse(ParseError),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "read failed: {err}"),
+            Self::Parse(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for ReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Parse(err) => err.source(),
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ParseError> for ReadError {
+    fn from(err: ParseError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+/// A NES file which only header is parsed, sections are read from the stream on demand.
+///
+/// All offsets are relative to the stream position when
+/// [`parse_reader`](fn.parse_reader.html) is called.
+#[derive(Debug)]
+pub struct NESFileReader<R> {
+    /// NES file header info
+    pub header: Header,
+    /// Where each section is located in the stream
+    pub layout: Layout,
+    reader: R,
+    base: u64,
+}
+
+/// Parse the header of a NES file stream, returns a reader to load other sections lazily.
+///
+/// Only 16 header bytes are read, the length of the stream is checked by seeking to its end.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::fs::File;
+///
+/// let file = File::open("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+/// let mut nes = dotnes::parse_reader(file).unwrap();
+/// let mut bank = vec![0; 8 * 1024];
+/// nes.read_prg_rom_at(8 * 1024, &mut bank).unwrap();
+/// ```
+///
+/// # Errors
+///
+/// When read or seek failed, return Err([`ReadError::Io`](enum.ReadError.html#variant.Io)).
+///
+/// When stream content is not valid NES format data, return
+/// Err([`ReadError::Parse`](enum.ReadError.html#variant.Parse)).
+pub fn parse_reader<R: Read + Seek>(mut reader: R) -> Result<NESFileReader<R>, ReadError> {
+    let base = reader.stream_position()?;
+    let end = reader.seek(SeekFrom::End(0))?;
+    let len = usize::try_from(end.saturating_sub(base)).unwrap_or(usize::MAX);
+
+    if len < HEADER_SIZE {
+        return Err(ParseError::Truncated {
+            section: Section::Header,
+            expected: HEADER_SIZE,
+            available: len,
+        }
+        .into());
+    }
+
+    let mut input = [0; HEADER_SIZE];
+    reader.seek(SeekFrom::Start(base))?;
+    reader.read_exact(&mut input)?;
+    let header = parse_header(&input).map_err(ParseError::from)?;
+
+    let layout = Layout::new(&header, len);
+    layout.check(&header)?;
+
+    Ok(NESFileReader { header, layout, reader, base })
+}
+
+impl<R: Read + Seek> NESFileReader<R> {
+    /// Offset range of trainer data, empty if not present, same as `layout.trainer`
+    #[must_use]
+    pub const fn trainer_range(&self) -> Range<u64> {
+        to_u64(&self.layout.trainer)
+    }
+
+    /// Offset range of PRG-ROM data, same as `layout.prg_rom`
+    #[must_use]
+    pub const fn prg_rom_range(&self) -> Range<u64> {
+        to_u64(&self.layout.prg_rom)
+    }
+
+    /// Offset range of CHR-ROM data, same as `layout.chr_rom`
+    #[must_use]
+    pub const fn chr_rom_range(&self) -> Range<u64> {
+        to_u64(&self.layout.chr_rom)
+    }
+
+    /// Offset range of miscellaneous ROMs, which is all bytes after CHR-ROM
+    #[must_use]
+    pub const fn miscellaneous_roms_range(&self) -> Range<u64> {
+        to_u64(&(self.layout.chr_rom.end..self.layout.file_len()))
+    }
+
+    /// Read the whole trainer data, empty if not present
+    ///
+    /// # Errors
+    ///
+    /// When read or seek failed.
+    pub fn read_trainer(&mut self) -> io::Result<Vec<u8>> {
+        self.read_range(self.layout.trainer.clone())
+    }
+
+    /// Read the whole PRG-ROM data
+    ///
+    /// # Errors
+    ///
+    /// When read or seek failed.
+    pub fn read_prg_rom(&mut self) -> io::Result<Vec<u8>> {
+        self.read_range(self.layout.prg_rom.clone())
+    }
+
+    /// Read the whole CHR-ROM data
+    ///
+    /// # Errors
+    ///
+    /// When read or seek failed.
+    pub fn read_chr_rom(&mut self) -> io::Result<Vec<u8>> {
+        self.read_range(self.layout.chr_rom.clone())
+    }
+
+    /// Read all miscellaneous ROMs data
+    ///
+    /// # Errors
+    ///
+    /// When read or seek failed.
+    pub fn read_miscellaneous_roms(&mut self) -> io::Result<Vec<u8>> {
+        self.read_range(self.layout.chr_rom.end..self.layout.file_len())
+    }
+
+    /// Fill `buf` with PRG-ROM data starts at `offset` of PRG-ROM.
+    ///
+    /// # Errors
+    ///
+    /// When read or seek failed, or the requested bytes exceed PRG-ROM, returns an error with
+    /// kind [`io::ErrorKind::InvalidInput`](https://doc.rust-lang.org/std/io/enum.ErrorKind.html).
+    pub fn read_prg_rom_at(&mut self, offset: usize, buf: &mut [u8]) -> io::Result<()> {
+        self.read_section_at(self.layout.prg_rom.clone(), offset, buf)
+    }
+
+    /// Fill `buf` with CHR-ROM data starts at `offset` of CHR-ROM.
+    ///
+    /// # Errors
+    ///
+    /// When read or seek failed, or the requested bytes exceed CHR-ROM, returns an error with
+    /// kind [`io::ErrorKind::InvalidInput`](https://doc.rust-lang.org/std/io/enum.ErrorKind.html).
+    pub fn read_chr_rom_at(&mut self, offset: usize, buf: &mut [u8]) -> io::Result<()> {
+        self.read_section_at(self.layout.chr_rom.clone(), offset, buf)
+    }
+
+    /// Read the PRG-ROM bank of `size` at `index`.
+    ///
+    /// # Errors
+    ///
+    /// When read or seek failed, or the bank exceeds PRG-ROM, returns an error with kind
+    /// [`io::ErrorKind::InvalidInput`](https://doc.rust-lang.org/std/io/enum.ErrorKind.html).
+    pub fn read_prg_bank(&mut self, size: BankSize, index: usize) -> io::Result<Vec<u8>> {
+        let mut buf = vec![0; size.bytes()];
+        self.read_prg_rom_at(index.saturating_mul(size.bytes()), &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Read the CHR-ROM bank of `size` at `index`.
+    ///
+    /// # Errors
+    ///
+    /// When read or seek failed, or the bank exceeds CHR-ROM, returns an error with kind
+    /// [`io::ErrorKind::InvalidInput`](https://doc.rust-lang.org/std/io/enum.ErrorKind.html).
+    pub fn read_chr_bank(&mut self, size: BankSize, index: usize) -> io::Result<Vec<u8>> {
+        let mut buf = vec![0; size.bytes()];
+        self.read_chr_rom_at(index.saturating_mul(size.bytes()), &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Get the underlying stream back
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn read_range(&mut self, range: Range<usize>) -> io::Result<Vec<u8>> {
+        let mut buf = vec![0; range.len()];
+        self.read_section_at(range, 0, &mut buf)?;
+        Ok(buf)
+    }
+
+    fn read_section_at(
+        &mut self, section: Range<usize>, offset: usize, buf: &mut [u8],
+    ) -> io::Result<()> {
+        let start = section.start.saturating_add(offset);
+        if start.saturating_add(buf.len()) > section.end {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "read out of section"));
+        }
+        self.reader.seek(SeekFrom::Start(self.base + start as u64))?;
+        self.reader.read_exact(buf)
+    }
+}
+
+const fn to_u64(range: &Range<usize>) -> Range<u64> {
+    range.start as u64..range.end as u64
+}
@@ -0,0 +1,132 @@
+//! Parse bad dumps which are truncated or overdumped
+
+use super::{parse_layout, Header, NESFile, ParseError, Section};
+
+/// A section which has fewer bytes than header declared
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Missing {
+    /// The incomplete section
+    pub section: Section,
+    /// Section size declared by header
+    pub expected: usize,
+    /// Bytes actually present for the section
+    pub available: usize,
+}
+
+impl Missing {
+    /// Count of missing bytes
+    #[must_use]
+    pub const fn count(&self) -> usize {
+        self.expected - self.available
+    }
+}
+
+/// A ROM section whose data is the same smaller block repeated multiple times
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Overdump {
+    /// The overdumped section, [`Section::PrgRom`] or [`Section::ChrRom`]
+    pub section: Section,
+    /// Section size declared by header
+    pub declared: usize,
+    /// Size of the repeated block, which is likely the real ROM size
+    pub suggested: usize,
+}
+
+/// Result of [`parse_recover`](fn.parse_recover.html)
+#[derive(Debug, Clone, Hash)]
+pub struct Recovered<'a> {
+    /// Parsed file, sections contain whatever data is present
+    pub file: NESFile<'a>,
+    /// Sections which are truncated, in file order
+    pub missing: Vec<Missing>,
+    /// ROM sections which are likely overdumped
+    pub overdumps: Vec<Overdump>,
+}
+
+impl Recovered<'_> {
+    /// If the file is complete and not overdumped.
+    ///
+    /// Trailing bytes are not considered, see
+    /// [`Layout::trailing`](struct.Layout.html#structfield.trailing) for them.
+    #[must_use]
+    pub fn is_good(&self) -> bool {
+        self.missing.is_empty() && self.overdumps.is_empty()
+    }
+
+    /// Header with ROM sizes corrected according detected overdumps.
+    #[must_use]
+    pub fn suggested_header(&self) -> Header {
+        let mut header = self.file.header.clone();
+        for overdump in &self.overdumps {
+            #[allow(clippy::cast_possible_truncation)] // smaller than size in header
+            let size = overdump.suggested as u32;
+            match overdump.section {
+                Section::PrgRom => header.prg_rom_size = size,
+                Section::ChrRom => header.chr_rom_size = size,
+                _ => {}
+            }
+        }
+        header
+    }
+}
+
+/// Parse NES file content like [`parse`](fn.parse.html), but accept truncated sections and
+/// detect overdumped ROMs.
+///
+/// # Examples
+///
+/// ```rust
+/// let data = std::fs::read("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+/// let recovered = dotnes::parse_recover(&data[..data.len() - 1]).unwrap();
+/// assert_eq!(recovered.missing[0].count(), 1);
+/// ```
+///
+/// # Errors
+///
+/// Only when header is incomplete or invalid, return Err([`ParseError`](enum.ParseError.html)).
+pub fn parse_recover<I: AsRef<[u8]> + ?Sized>(input: &I) -> Result<Recovered<'_>, ParseError> {
+    let input = input.as_ref();
+
+    let (header, layout) = parse_layout(input)?;
+
+    let missing = layout
+        .shortages(&header)
+        .map(|(section, expected, available)| Missing { section, expected, available })
+        .collect::<Vec<_>>();
+
+    let file = NESFile::split(input, header, layout);
+
+    let overdumps = [(Section::PrgRom, file.prg_rom), (Section::ChrRom, file.chr_rom)]
+        .iter()
+        .filter(|(section, _)| missing.iter().all(|x| x.section != *section))
+        .filter_map(|&(section, data)| {
+            let unit = if section == Section::PrgRom { PRG_ROM_UNIT } else { CHR_ROM_UNIT };
+            let suggested = repeated_block_size(data, unit);
+            if suggested < data.len() {
+                Some(Overdump { section, declared: data.len(), suggested })
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    Ok(Recovered { file, missing, overdumps })
+}
+
+/// Smallest PRG-ROM size of iNES header
+const PRG_ROM_UNIT: usize = 16 * 1024;
+/// Smallest CHR-ROM size of iNES header
+const CHR_ROM_UNIT: usize = 8 * 1024;
+
+/// Find the smallest power-of-two fraction of `data`, but not smaller than `unit`, which repeats
+/// to fill it. Data of a single byte value, like blank CHR-ROM, is not considered repeated.
+fn repeated_block_size(data: &[u8], unit: usize) -> usize {
+    if data.windows(2).all(|x| x[0] == x[1]) {
+        return data.len();
+    }
+    let mut size = data.len();
+    while size / 2 >= unit && size % 2 == 0 && data[..size / 2] == data[size / 2..size] {
+        size /= 2;
+    }
+    size
+}
@@ -0,0 +1,176 @@
+//! ld65 debug info, lines of a record type, a tab and comma separated attributes, like
+//! `sym id=0,name="reset",addrsize=absolute,val=0xC000,seg=0,type=lab`.
+//!
+//! A segment written to the output file has `ooffs`, the file offset of its `start` address.
+
+use {
+    super::{prg_ram_address, prg_rom_address, Location, Symbol, SymbolError},
+    crate::NESFile,
+    std::collections::{BTreeMap, HashMap},
+};
+
+/// A segment, with the file offset of its start if it's written to the output file
+#[derive(Debug, Copy, Clone)]
+struct Segment {
+    start: u32,
+    file_offset: Option<usize>,
+}
+
+/// A label before segments are resolved
+struct Label<'a> {
+    name: &'a str,
+    value: u32,
+    segment: Option<u32>,
+    size: usize,
+}
+
+pub fn parse(text: &str, file: &NESFile<'_>) -> Result<Vec<Symbol>, SymbolError> {
+    let mut segments = HashMap::new();
+    let mut labels = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let error = || SymbolError::Syntax { line: index + 1 };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some((record, rest)) = line.split_once(char::is_whitespace) else {
+            return Err(error());
+        };
+        let attributes = attributes(rest).ok_or_else(error)?;
+        let get = |key| attributes.iter().find(|(x, _)| *x == key).map(|(_, value)| *value);
+        let number = |key| get(key).map(|x| number(x).ok_or_else(error)).transpose();
+
+        match record {
+            "seg" => {
+                let id = number("id")?.ok_or_else(error)?;
+                let start = number("start")?.ok_or_else(error)?;
+                let file_offset = number("ooffs")?.map(|x| x as usize);
+                segments.insert(id, Segment { start, file_offset });
+            }
+            "sym" if get("type") == Some("lab") => labels.push(Label {
+                name: get("name").ok_or_else(error)?,
+                value: number("val")?.ok_or_else(error)?,
+                segment: number("seg")?,
+                size: number("size")?.map_or(1, |x| x as usize),
+            }),
+            _ => {}
+        }
+    }
+
+    labels.into_iter().map(|label| resolve(&label, &segments, file)).collect()
+}
+
+fn resolve(
+    label: &Label<'_>, segments: &HashMap<u32, Segment>, file: &NESFile<'_>,
+) -> Result<Symbol, SymbolError> {
+    let segment = match label.segment {
+        Some(id) => Some(*segments.get(&id).ok_or(SymbolError::UnknownSegment { id })?),
+        None => None,
+    };
+    let prg_rom = segment.and_then(|segment| {
+        let delta = label.value.checked_sub(segment.start)? as usize;
+        file.layout.file_to_prg_rom(segment.file_offset? + delta)
+    });
+
+    #[allow(clippy::cast_possible_truncation)] // CPU address space is 16 bits
+    let address = label.value as u16;
+    let location = prg_rom.map_or(Location::Cpu(address), |offset| Location::PrgRom {
+        offset,
+        address: Some(address),
+    });
+    Ok(Symbol { name: label.name.to_owned(), location, size: label.size, comment: None })
+}
+
+/// Split `key=value` pairs by commas which are not quoted, quotes are removed from values
+fn attributes(text: &str) -> Option<Vec<(&str, &str)>> {
+    let mut attributes = Vec::new();
+    let mut rest = text.trim();
+    while !rest.is_empty() {
+        let (key, value) = rest.split_once('=')?;
+        let end = if let Some(quoted) = value.strip_prefix('"') {
+            quoted.find('"')? + 2
+        } else {
+            value.find(',').unwrap_or(value.len())
+        };
+        attributes.push((key, value[..end].trim_matches('"')));
+        let after = &value[end..];
+        rest = after.strip_prefix(',').unwrap_or(after);
+    }
+    Some(attributes)
+}
+
+fn number(text: &str) -> Option<u32> {
+    text.strip_prefix("0x")
+        .map_or_else(|| text.parse().ok(), |hex| u32::from_str_radix(hex, 16).ok())
+}
+
+pub fn write(symbols: &[Symbol], file: &NESFile<'_>) -> String {
+    // PRG-ROM segments by the distance from CPU address to file offset, as (start, end)
+    let mut segments: BTreeMap<usize, (u16, u16)> = BTreeMap::new();
+    let mut labels = Vec::new();
+    for symbol in symbols {
+        let (address, key) = match symbol.location {
+            Location::PrgRom { offset, address } => {
+                let address = prg_rom_address(file, offset, address);
+                let key =
+                    file.layout.prg_rom_to_file(offset).map(|x| x.wrapping_sub(address.into()));
+                (address, key)
+            }
+            Location::Cpu(address) => (address, None),
+            Location::SaveRam(offset) | Location::WorkRam(offset) => {
+                (prg_ram_address(offset), None)
+            }
+        };
+        if let Some(key) = key {
+            #[allow(clippy::cast_possible_truncation)] // symbols are in 16 bit address space
+            let last = address.saturating_add(symbol.size.saturating_sub(1) as u16);
+            let (start, end) = segments.entry(key).or_insert((address, last));
+            *start = u16::min(*start, address);
+            *end = u16::max(*end, last);
+        }
+        labels.push((symbol, address, key));
+    }
+
+    let ram_id = segments.len();
+    let mut text = segment_lines(&segments, labels.len());
+
+    let ids: HashMap<usize, usize> =
+        segments.keys().enumerate().map(|(id, &key)| (key, id)).collect();
+    for (id, (symbol, address, key)) in labels.into_iter().enumerate() {
+        let segment = key.map_or(ram_id, |x| ids[&x]);
+        let addrsize = if address < 0x100 { "zeropage" } else { "absolute" };
+        let line = format!(
+            "sym\tid={id},name=\"{}\",addrsize={addrsize},size={},val=0x{address:X},seg={segment},\
+             type=lab\n",
+            symbol.name, symbol.size
+        );
+        text.push_str(&line);
+    }
+    text
+}
+
+/// Header and segment records, PRG-ROM segments are followed by a RAM segment
+fn segment_lines(segments: &BTreeMap<usize, (u16, u16)>, label_count: usize) -> String {
+    let ram_id = segments.len();
+    let mut text = "version\tmajor=2,minor=0\n".to_owned();
+    let line = format!(
+        "info\tcsym=0,file=0,lib=0,line=0,mod=0,scope=0,seg={},span=0,sym={label_count},type=0\n",
+        ram_id + 1,
+    );
+    text.push_str(&line);
+
+    for (id, (&key, &(start, end))) in segments.iter().enumerate() {
+        let size = usize::from(end - start) + 1;
+        let file_offset = key.wrapping_add(start.into());
+        let line = format!(
+            "seg\tid={id},name=\"PRG{id}\",start=0x{start:06X},size=0x{size:04X},\
+             addrsize=absolute,type=ro,ooffs={file_offset}\n"
+        );
+        text.push_str(&line);
+    }
+    let line = format!(
+        "seg\tid={ram_id},name=\"RAM\",start=0x000000,size=0x8000,addrsize=absolute,type=rw\n"
+    );
+    text.push_str(&line);
+    text
+}
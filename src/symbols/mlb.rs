@@ -0,0 +1,66 @@
+//! Mesen labels, lines like `P:1F0A:Reset:Comment` or `R:0300-030F:Buffer`. The address is an
+//! offset in the memory type, and new lines in comments are escaped as `\n`.
+
+use {
+    super::{Location, Symbol, SymbolError},
+    std::convert::TryFrom,
+};
+
+pub fn parse(text: &str) -> Result<Vec<Symbol>, SymbolError> {
+    let mut symbols = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let error = || SymbolError::Syntax { line: index + 1 };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut fields = line.splitn(4, ':');
+        let (Some(kind), Some(range), Some(name)) = (fields.next(), fields.next(), fields.next())
+        else {
+            return Err(error());
+        };
+        let comment = fields.next().filter(|x| !x.is_empty()).map(|x| x.replace("\\n", "\n"));
+        let hex = |text| usize::from_str_radix(text, 16).map_err(|_| error());
+        let (start, end) = match range.split_once('-') {
+            Some((start, end)) => (hex(start)?, hex(end)?),
+            None => (hex(range)?, hex(range)?),
+        };
+        let size = end.checked_sub(start).ok_or_else(error)? + 1;
+        let cpu = || u16::try_from(start).map(Location::Cpu).map_err(|_| error());
+
+        let location = match kind {
+            "P" | "NesPrgRom" => Location::PrgRom { offset: start, address: None },
+            "R" | "NesInternalRam" | "G" | "NesMemory" => cpu()?,
+            "S" | "NesSaveRam" => Location::SaveRam(start),
+            "W" | "NesWorkRam" => Location::WorkRam(start),
+            _ => continue,
+        };
+        symbols.push(Symbol { name: name.to_owned(), location, size, comment });
+    }
+    Ok(symbols)
+}
+
+pub fn write(symbols: &[Symbol]) -> String {
+    let mut text = String::new();
+    for symbol in symbols {
+        let (kind, start) = match symbol.location {
+            Location::PrgRom { offset, .. } => ('P', offset),
+            Location::Cpu(address) if address < 0x2000 => ('R', usize::from(address & 0x07FF)),
+            Location::Cpu(address) => ('G', usize::from(address)),
+            Location::SaveRam(offset) => ('S', offset),
+            Location::WorkRam(offset) => ('W', offset),
+        };
+
+        let mut line = format!("{kind}:{start:04X}");
+        if symbol.size > 1 {
+            line = format!("{line}-{:04X}", start + symbol.size - 1);
+        }
+        line = format!("{line}:{}", symbol.name);
+        if let Some(comment) = &symbol.comment {
+            line = format!("{line}:{}", comment.replace('\n', "\\n"));
+        }
+        text.push_str(&line);
+        text.push('\n');
+    }
+    text
+}
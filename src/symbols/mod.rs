@@ -0,0 +1,220 @@
+//! Debugger symbol files, to pass labels between assemblers, disassemblers and emulators
+//!
+//! Three formats are supported: FCEUX name lists (`.nl`), which have one file for RAM and one
+//! for each 16KB PRG-ROM bank, Mesen labels (`.mlb`), and ld65 debug info (`.dbg`) written by
+//! `ld65 --dbgfile`. All of them are converted to PRG-ROM offsets, so the header and trainer
+//! never need to be counted by hand.
+
+mod dbg;
+mod mlb;
+mod nl;
+
+use {
+    super::{disasm, NESFile},
+    std::{error::Error, fmt},
+};
+
+/// Where a symbol is
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Location {
+    /// In PRG-ROM
+    PrgRom {
+        /// Offset in PRG-ROM
+        offset: usize,
+        /// CPU address the symbol is used at, if the file has it
+        address: Option<u16>,
+    },
+    /// CPU address out of PRG-ROM, like RAM and registers
+    Cpu(u16),
+    /// Offset in battery backed PRG-RAM
+    SaveRam(usize),
+    /// Offset in PRG-RAM without battery
+    WorkRam(usize),
+}
+
+/// A named location
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Symbol {
+    /// Symbol name, can be empty for a comment only
+    pub name: String,
+    /// Where the symbol is
+    pub location: Location,
+    /// Bytes covered, 1 for a single byte label, more for an array
+    pub size: usize,
+    /// Comment, lines are separated by `\n`
+    pub comment: Option<String>,
+}
+
+impl Symbol {
+    /// Offset in PRG-ROM, `None` if the symbol is not in PRG-ROM.
+    #[must_use]
+    pub const fn prg_rom_offset(&self) -> Option<usize> {
+        match self.location {
+            Location::PrgRom { offset, .. } => Some(offset),
+            _ => None,
+        }
+    }
+
+    /// Offset in the NES file, counting header and trainer of `file`. `None` if the symbol is
+    /// not in PRG-ROM.
+    #[must_use]
+    pub fn file_offset(&self, file: &NESFile<'_>) -> Option<usize> {
+        file.layout.prg_rom_to_file(self.prg_rom_offset()?)
+    }
+}
+
+/// A FCEUX name list file
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum NlFile {
+    /// `game.nes.ram.nl`, addresses below $8000
+    Ram,
+    /// `game.nes.X.nl`, the 16KB PRG-ROM bank of hexadecimal number `X`
+    Bank(usize),
+}
+
+impl NlFile {
+    /// Suffix appended to the ROM file name, like `.ram.nl` or `.1F.nl`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dotnes::symbols::NlFile;
+    ///
+    /// assert_eq!(NlFile::Bank(31).suffix(), ".1F.nl");
+    /// assert_eq!(format!("game.nes{}", NlFile::Ram.suffix()), "game.nes.ram.nl");
+    /// ```
+    #[must_use]
+    pub fn suffix(self) -> String {
+        match self {
+            Self::Ram => ".ram.nl".to_owned(),
+            Self::Bank(bank) => format!(".{bank:X}.nl"),
+        }
+    }
+}
+
+/// Load symbol file failed reason
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum SymbolError {
+    /// A line can't be parsed
+    Syntax {
+        /// Line number, starts from 1
+        line: usize,
+    },
+    /// A symbol of ld65 debug info refers to a segment which is not defined
+    UnknownSegment {
+        /// Segment id
+        id: u32,
+    },
+}
+
+impl fmt::Display for SymbolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax { line } => write!(f, "invalid syntax at line {line}"),
+            Self::UnknownSegment { id } => write!(f, "segment {id} is not defined"),
+        }
+    }
+}
+
+impl Error for SymbolError {}
+
+/// Symbols loaded from symbol files
+///
+/// # Examples
+///
+/// ```rust
+/// use dotnes::symbols::{Location, NlFile, SymbolTable};
+///
+/// let data = std::fs::read("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+/// let nes = dotnes::parse(&data).unwrap();
+///
+/// let symbols = SymbolTable::from_nl("$E124#reset#Entry point\n", NlFile::Bank(0)).unwrap();
+/// let reset = symbols.find("reset").unwrap();
+/// assert_eq!(reset.location, Location::PrgRom { offset: 0x2124, address: Some(0xE124) });
+/// assert_eq!(reset.file_offset(&nes), Some(0x2134));
+/// assert_eq!(symbols.to_mlb(), "P:2124:reset:Entry point\n");
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct SymbolTable {
+    /// All symbols, in the order of files
+    pub symbols: Vec<Symbol>,
+}
+
+impl SymbolTable {
+    /// Find a symbol by name.
+    #[must_use]
+    pub fn find(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.iter().find(|x| x.name == name)
+    }
+
+    /// Load a FCEUX name list, `file` tells which one it is. Addresses below $8000 in a bank
+    /// file are out of PRG-ROM.
+    ///
+    /// # Errors
+    ///
+    /// When a line can't be parsed, return Err([`SymbolError`](enum.SymbolError.html)).
+    pub fn from_nl(text: &str, file: NlFile) -> Result<Self, SymbolError> {
+        nl::parse(text, file).map(|symbols| Self { symbols })
+    }
+
+    /// Write FCEUX name lists, one for RAM and each 16KB PRG-ROM bank which has symbols.
+    ///
+    /// PRG-ROM symbols without CPU address are written at their address at power on in `file`,
+    /// or in the window at $8000 if they are not mapped at power on. Save RAM and work RAM
+    /// symbols are written at $6000.
+    #[must_use]
+    pub fn to_nl(&self, file: &NESFile<'_>) -> Vec<(NlFile, String)> {
+        nl::write(&self.symbols, file)
+    }
+
+    /// Load Mesen labels. Both the single letter memory types of Mesen and the names of Mesen 2,
+    /// like `NesPrgRom`, are supported, labels of other memory types are skipped.
+    ///
+    /// # Errors
+    ///
+    /// When a line can't be parsed, return Err([`SymbolError`](enum.SymbolError.html)).
+    pub fn from_mlb(text: &str) -> Result<Self, SymbolError> {
+        mlb::parse(text).map(|symbols| Self { symbols })
+    }
+
+    /// Write Mesen labels with single letter memory types. CPU addresses below $2000 are
+    /// internal RAM, others are written as registers.
+    #[must_use]
+    pub fn to_mlb(&self) -> String {
+        mlb::write(&self.symbols)
+    }
+
+    /// Load labels of ld65 debug info. Labels in segments written to `file` are resolved to
+    /// PRG-ROM by their output offsets, others are CPU addresses. Constants and imports are
+    /// skipped.
+    ///
+    /// # Errors
+    ///
+    /// When a line can't be parsed or a segment is missing, return
+    /// Err([`SymbolError`](enum.SymbolError.html)).
+    pub fn from_dbg(text: &str, file: &NESFile<'_>) -> Result<Self, SymbolError> {
+        dbg::parse(text, file).map(|symbols| Self { symbols })
+    }
+
+    /// Write ld65 debug info with only segments and labels, which is enough for debuggers to
+    /// find labels. Comments are not written, CPU addresses are decided like
+    /// [`to_nl`](#method.to_nl).
+    #[must_use]
+    pub fn to_dbg(&self, file: &NESFile<'_>) -> String {
+        dbg::write(&self.symbols, file)
+    }
+}
+
+/// CPU address of a PRG-ROM symbol, see [`SymbolTable::to_nl`]
+fn prg_rom_address(file: &NESFile<'_>, offset: usize, address: Option<u16>) -> u16 {
+    #[allow(clippy::cast_possible_truncation)] // less than 16K
+    let window = 0x8000 | (offset % 0x4000) as u16;
+    address.or_else(|| disasm::cpu_address(file, offset)).unwrap_or(window)
+}
+
+/// CPU address of a PRG-RAM offset, it's mapped at $6000
+const fn prg_ram_address(offset: usize) -> u16 {
+    #[allow(clippy::cast_possible_truncation)] // PRG-RAM window is 8K
+    let offset = offset as u16;
+    0x6000_u16.wrapping_add(offset)
+}
@@ -0,0 +1,73 @@
+//! FCEUX name lists, lines like `$C000#Reset#Comment`, or `$0300/10#Buffer#` for 16 bytes.
+//! A line starting with `\` continues the comment of the previous line.
+
+use {
+    super::{prg_ram_address, prg_rom_address, Location, NlFile, Symbol, SymbolError},
+    crate::NESFile,
+    std::collections::BTreeMap,
+};
+
+pub fn parse(text: &str, file: NlFile) -> Result<Vec<Symbol>, SymbolError> {
+    let mut symbols: Vec<Symbol> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let error = || SymbolError::Syntax { line: index + 1 };
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix('\\') {
+            let symbol = symbols.last_mut().ok_or_else(error)?;
+            match symbol.comment {
+                Some(ref mut comment) => {
+                    comment.push('\n');
+                    comment.push_str(rest);
+                }
+                None => symbol.comment = Some(rest.to_owned()),
+            }
+            continue;
+        }
+
+        let mut fields = line.strip_prefix('$').ok_or_else(error)?.splitn(3, '#');
+        let (Some(address), Some(name)) = (fields.next(), fields.next()) else {
+            return Err(error());
+        };
+        let comment = fields.next().filter(|x| !x.is_empty()).map(str::to_owned);
+        let (address, size) = match address.split_once('/') {
+            Some((address, size)) => {
+                (address, usize::from_str_radix(size, 16).map_err(|_| error())?)
+            }
+            None => (address, 1),
+        };
+        let address = u16::from_str_radix(address, 16).map_err(|_| error())?;
+
+        let location = match file {
+            NlFile::Bank(bank) if address >= 0x8000 => Location::PrgRom {
+                offset: bank * 0x4000 + usize::from(address & 0x3FFF),
+                address: Some(address),
+            },
+            _ => Location::Cpu(address),
+        };
+        symbols.push(Symbol { name: name.to_owned(), location, size, comment });
+    }
+    Ok(symbols)
+}
+
+pub fn write(symbols: &[Symbol], file: &NESFile<'_>) -> Vec<(NlFile, String)> {
+    let mut files: BTreeMap<NlFile, String> = BTreeMap::new();
+    for symbol in symbols {
+        let (nl_file, address) = match symbol.location {
+            Location::PrgRom { offset, address } => {
+                (NlFile::Bank(offset / 0x4000), prg_rom_address(file, offset, address))
+            }
+            Location::Cpu(address) => (NlFile::Ram, address),
+            Location::SaveRam(offset) | Location::WorkRam(offset) => {
+                (NlFile::Ram, prg_ram_address(offset))
+            }
+        };
+
+        let size = if symbol.size > 1 { format!("/{:X}", symbol.size) } else { String::new() };
+        let comment = symbol.comment.as_deref().unwrap_or_default().replace('\n', "\n\\");
+        let line = format!("${address:04X}{size}#{}#{comment}\n", symbol.name);
+        files.entry(nl_file).or_default().push_str(&line);
+    }
+    files.into_iter().collect()
+}
@@ -0,0 +1,145 @@
+//! Read and check the interrupt vectors at $FFFA - $FFFF
+
+use {
+    super::{
+        mapper::{BankState, MemoryMap},
+        NESFile,
+    },
+    std::fmt,
+};
+
+/// An interrupt vector
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Vector {
+    /// NMI vector at $FFFA, the PPU triggers it at vertical blank
+    Nmi,
+    /// Reset vector at $FFFC, where the program starts
+    Reset,
+    /// IRQ/BRK vector at $FFFE
+    Irq,
+}
+
+impl fmt::Display for Vector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Nmi => "NMI",
+            Self::Reset => "RESET",
+            Self::Irq => "IRQ",
+        })
+    }
+}
+
+/// A suspicious interrupt vector
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum VectorProblem {
+    /// Vector is $0000 or $FFFF, which is usually blank data of a bad dump when it's the reset
+    /// vector
+    Blank {
+        /// The vector
+        vector: Vector,
+        /// Address it points to
+        address: u16,
+    },
+    /// Vector points to an address not mapped to PRG-ROM at power on. The NMI and IRQ vectors
+    /// of some games point to RAM on purpose
+    NotPrgRom {
+        /// The vector
+        vector: Vector,
+        /// Address it points to
+        address: u16,
+    },
+}
+
+impl VectorProblem {
+    /// The vector with the problem
+    #[must_use]
+    pub const fn vector(&self) -> Vector {
+        match *self {
+            Self::Blank { vector, .. } | Self::NotPrgRom { vector, .. } => vector,
+        }
+    }
+}
+
+impl fmt::Display for VectorProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Blank { vector, address } => write!(f, "{vector} vector is ${address:04X}"),
+            Self::NotPrgRom { vector, address } => {
+                write!(f, "{vector} vector ${address:04X} is not in PRG-ROM")
+            }
+        }
+    }
+}
+
+/// Interrupt vectors of a file, read by [`NESFile::vectors`](struct.NESFile.html#method.vectors)
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Vectors {
+    /// NMI handler address
+    pub nmi: u16,
+    /// Reset handler address
+    pub reset: u16,
+    /// IRQ/BRK handler address
+    pub irq: u16,
+}
+
+impl Vectors {
+    /// Read vectors from `file`. $FFFA - $FFFF are translated by the mapper's power on bank
+    /// state, or the last 6 bytes of PRG-ROM are used if the mapper is not supported.
+    pub(crate) fn read(file: &NESFile<'_>) -> Option<Self> {
+        let map = memory_map(file);
+        let read = |address: u16| {
+            let offset = match &map {
+                Some(map) => map.cpu_to_prg_rom(address)?,
+                None => file.prg_rom.len().checked_sub(0x1_0000 - usize::from(address))?,
+            };
+            let bytes = file.prg_rom.get(offset..offset + 2)?;
+            Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+        };
+        Some(Self { nmi: read(0xFFFA)?, reset: read(0xFFFC)?, irq: read(0xFFFE)? })
+    }
+
+    /// Check vectors point to PRG-ROM of `file` at power on, and are not blank. Without mapper
+    /// support, all addresses from $8000 are considered PRG-ROM.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dotnes::{Vector, VectorProblem, Vectors};
+    ///
+    /// let data = std::fs::read("tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes").unwrap();
+    /// let nes = dotnes::parse(&data).unwrap();
+    /// let vectors = nes.vectors().unwrap();
+    /// assert!(vectors.problems(&nes).is_empty());
+    ///
+    /// let vectors = Vectors { reset: 0xFFFF, nmi: 0x0700, ..vectors };
+    /// assert_eq!(
+    ///     vectors.problems(&nes),
+    ///     [
+    ///         VectorProblem::NotPrgRom { vector: Vector::Nmi, address: 0x0700 },
+    ///         VectorProblem::Blank { vector: Vector::Reset, address: 0xFFFF },
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn problems(&self, file: &NESFile<'_>) -> Vec<VectorProblem> {
+        let map = memory_map(file);
+        let mut problems = Vec::new();
+        for &(vector, address) in
+            &[(Vector::Nmi, self.nmi), (Vector::Reset, self.reset), (Vector::Irq, self.irq)]
+        {
+            if address == 0x0000 || address == 0xFFFF {
+                problems.push(VectorProblem::Blank { vector, address });
+            } else if !map
+                .as_ref()
+                .map_or(address >= 0x8000, |x| x.cpu_to_prg_rom(address).is_some())
+            {
+                problems.push(VectorProblem::NotPrgRom { vector, address });
+            }
+        }
+        problems
+    }
+}
+
+fn memory_map(file: &NESFile<'_>) -> Option<MemoryMap> {
+    BankState::power_on(file.header.mapper).map(|state| MemoryMap::for_file(file, &state))
+}
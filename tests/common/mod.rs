@@ -0,0 +1,27 @@
+//! Helpers shared by integration tests
+
+#![allow(dead_code)] // every test crate uses only some of them
+
+use dotnes::header::Header;
+
+/// NROM test ROM with 16KB PRG-ROM and CHR-RAM
+pub const ROM: &str = "tests/roms/cpu_tests/branch_timing_tests/1.Branch_Basics.nes";
+
+/// NES file bytes of `mapper` with `prg_rom` and `chr_rom`, header sizes are set from them.
+pub fn nes_file(mapper: u16, prg_rom: &[u8], chr_rom: &[u8]) -> Vec<u8> {
+    #[allow(clippy::cast_possible_truncation)] // test ROMs are small
+    let header = Header::new(mapper, prg_rom.len() as u32, chr_rom.len() as u32);
+    nes_file_with_header(&header, prg_rom, chr_rom)
+}
+
+/// NES file bytes of `header` with `prg_rom` and `chr_rom`, and a blank trainer if the header
+/// has one. Sizes in `header` are written as is.
+pub fn nes_file_with_header(header: &Header, prg_rom: &[u8], chr_rom: &[u8]) -> Vec<u8> {
+    let mut data = header.to_bytes().to_vec();
+    if header.has_trainer {
+        data.resize(data.len() + 512, 0);
+    }
+    data.extend_from_slice(prg_rom);
+    data.extend_from_slice(chr_rom);
+    data
+}
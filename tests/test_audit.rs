@@ -0,0 +1,115 @@
+mod common;
+
+use {
+    common::ROM,
+    dotnes::{database::Database, Issue, ParseError},
+    std::{fs, io, path::PathBuf},
+};
+
+#[test]
+fn audit_collection() {
+    let data = fs::read(ROM).unwrap();
+    let crc = dotnes::crc32(&data[16..]);
+
+    let mut vertical = data.clone();
+    vertical[6] |= 0x01;
+    let mut overdumped = data.clone();
+    overdumped[4] = 2;
+    overdumped.extend_from_within(16..);
+    let mut truncated = data.clone();
+    truncated.truncate(1000);
+
+    let path = |name: &str| PathBuf::from(name);
+    let files = vec![
+        (path("a.nes"), data.clone()),
+        (path("b.nes"), vertical),
+        (path("c.nes"), data),
+        (path("d.nes"), overdumped),
+        (path("e.nes"), truncated),
+        (path("f.nes"), b"not a nes file".to_vec()),
+    ];
+
+    let xml = format!(
+        r#"<datafile>
+            <game name="Branch Basics (World) [h1]">
+                <rom name="a.nes" size="16384" crc="{crc:08X}"
+                    header="4E 45 53 1A 01 00 00 00 00 00 00 00 00 00 00 00"/>
+            </game>
+        </datafile>"#
+    );
+    let database = Database::from_dat(&xml).unwrap();
+    let audit = dotnes::audit(files, Some(&database));
+    assert_eq!(audit.files.len(), 6);
+    assert!(audit.files[5].header.is_none());
+
+    let issues: Vec<_> = audit.issues.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        issues,
+        [
+            "a.nes: hack (Branch Basics (World) [h1])",
+            "b.nes: header differs from database in mirroring (Branch Basics (World) [h1])",
+            "b.nes: hack (Branch Basics (World) [h1])",
+            "c.nes: hack (Branch Basics (World) [h1])",
+            "d.nes: overdumped, PRG-ROM should be 16384 bytes",
+            "e.nes: truncated, 15400 bytes missing",
+            "f.nes: header is truncated, expected 16 bytes but only 14 available",
+            "duplicates: a.nes, c.nes",
+            "same data with different headers: a.nes, b.nes, c.nes",
+        ]
+    );
+    assert!(
+        matches!(&audit.issues[0], Issue::Hack { path, .. } if path == &PathBuf::from("a.nes"))
+    );
+}
+
+#[test]
+fn audit_reports_every_database_flag() {
+    let data = fs::read(ROM).unwrap();
+    let crc = dotnes::crc32(&data[16..]);
+    let xml = format!(
+        r#"<datafile>
+            <game name="Branch Basics (World) [T-Fre][h1][b]">
+                <rom name="a.nes" size="16384" crc="{crc:08X}"/>
+            </game>
+        </datafile>"#
+    );
+    let database = Database::from_dat(&xml).unwrap();
+    let audit = dotnes::audit(vec![(PathBuf::from("a.nes"), data)], Some(&database));
+
+    let issues: Vec<_> = audit.issues.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        issues,
+        [
+            "a.nes: hack (Branch Basics (World) [T-Fre][h1][b])",
+            "a.nes: translation (Branch Basics (World) [T-Fre][h1][b])",
+            "a.nes: known bad dump (Branch Basics (World) [T-Fre][h1][b])",
+        ]
+    );
+}
+
+#[test]
+fn audit_dir_survives_bad_files() {
+    let dir = std::env::temp_dir().join(format!("dotnes-audit-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::copy(ROM, dir.join("a.nes")).unwrap();
+    // NES 2.0 PRG-ROM size 2^63 * 7 bytes in exponent-multiplier notation
+    let junk = *b"NES\x1A\xFF\xFF\x00\x08\x00\xFF\x00\x00\x00\x00\x00\x00";
+    fs::write(dir.join("b.nes"), junk).unwrap();
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(dir.join("missing"), dir.join("c.nes")).unwrap();
+
+    let audit = dotnes::audit_dir(&dir, None);
+    fs::remove_dir_all(&dir).unwrap();
+    let audit = audit.unwrap();
+
+    assert_eq!(audit.files.len(), 2);
+    assert!(matches!(
+        &audit.issues[0],
+        Issue::Unparsable { path, error: ParseError::InvalidHeader(_) } if path.ends_with("b.nes")
+    ));
+    #[cfg(unix)]
+    assert!(matches!(
+        &audit.issues[1],
+        Issue::Unreadable { path, kind: io::ErrorKind::NotFound, .. } if path.ends_with("c.nes")
+    ));
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use dotnes::{
+    cdl::{CdlError, ChrCoverage, ChrFlags, CodeDataLog, PrgCoverage, PrgFlags},
+    BankError, BankSize,
+};
+
+fn nes_file() -> Vec<u8> {
+    common::nes_file(0, &[0; 32 * 1024], &[0; 8 * 1024])
+}
+
+#[test]
+fn parse_and_write_cdl() {
+    let data = nes_file();
+    let nes = dotnes::parse(&data).unwrap();
+
+    let mut cdl = vec![0; 40 * 1024];
+    cdl[0x0000] = 0x01;
+    cdl[0x7FFC] = 0x0E;
+    cdl[0x4000] = 0x42 | 0x04;
+    cdl[0x8000] = 0x01;
+    cdl[0x9FFF] = 0x03;
+    let log = CodeDataLog::parse(&cdl, &nes).unwrap();
+    assert_eq!(log.prg.len(), 32 * 1024);
+    assert_eq!(log.chr.len(), 8 * 1024);
+
+    assert_eq!(log.prg[0].window(), 0x8000);
+    assert!(log.prg[0x7FFC].contains(PrgFlags::DATA));
+    assert_eq!(log.prg[0x7FFC].window(), 0xE000);
+    assert!(log.prg[0x4000].contains(PrgFlags::PCM | PrgFlags::DATA));
+    assert_eq!(log.prg[0x4000].window(), 0xA000);
+    assert!(log.chr[0].contains(ChrFlags::RENDERED));
+    assert!(log.chr[0x1FFF].contains(ChrFlags::RENDERED | ChrFlags::READ));
+    assert_eq!(log.to_bytes(), cdl);
+
+    // CHR-ROM part is optional
+    let log = CodeDataLog::parse(&cdl[..32 * 1024], &nes).unwrap();
+    assert!(log.chr.iter().all(|x| !x.is_logged()));
+
+    assert_eq!(
+        CodeDataLog::parse(&cdl[..100], &nes),
+        Err(CdlError::WrongSize { size: 100, prg_rom_size: 32 * 1024, chr_rom_size: 8 * 1024 })
+    );
+}
+
+#[test]
+fn merge_and_coverage() {
+    let data = nes_file();
+    let nes = dotnes::parse(&data).unwrap();
+
+    let mut first = CodeDataLog::new(&nes);
+    first.prg[0] = PrgFlags::CODE.with_window(0x8000);
+    first.prg[1] = PrgFlags::CODE.with_window(0xC001);
+    first.chr[0] = ChrFlags::RENDERED;
+
+    let mut second = CodeDataLog::new(&nes);
+    second.prg[1] = PrgFlags::DATA.with_window(0x8001);
+    second.prg[0x4000] = PrgFlags::DATA.with_window(0xC000);
+    second.chr[0] = ChrFlags::READ;
+    second.chr[0x1000] = ChrFlags::READ;
+
+    first.merge(&second).unwrap();
+    // the window of the first log is kept
+    assert_eq!(first.prg[1], (PrgFlags::CODE | PrgFlags::DATA).with_window(0xC000));
+    assert_eq!(first.prg[0x4000].window(), 0xC000);
+    assert_eq!(first.chr[0], ChrFlags::RENDERED | ChrFlags::READ);
+
+    assert_eq!(
+        first.prg_coverage(BankSize::Size16K).unwrap(),
+        [
+            PrgCoverage { bank: 0, bytes: 0x4000, code: 2, data: 1, logged: 2 },
+            PrgCoverage { bank: 1, bytes: 0x4000, code: 0, data: 1, logged: 1 },
+        ]
+    );
+    assert_eq!(
+        first.chr_coverage(BankSize::Size4K).unwrap(),
+        [
+            ChrCoverage { bank: 0, bytes: 0x1000, rendered: 1, read: 1, logged: 1 },
+            ChrCoverage { bank: 1, bytes: 0x1000, rendered: 0, read: 1, logged: 1 },
+        ]
+    );
+    assert_eq!(
+        first.prg_coverage(BankSize::Size32K).unwrap()[0],
+        PrgCoverage { bank: 0, bytes: 0x8000, code: 2, data: 2, logged: 3 }
+    );
+
+    let mut smaller = first.clone();
+    smaller.chr.clear();
+    assert_eq!(smaller.chr_coverage(BankSize::Size8K), Ok(Vec::new()));
+    assert_eq!(first.merge(&smaller), Err(CdlError::DifferentSizes));
+
+    smaller.prg.truncate(100);
+    assert_eq!(
+        smaller.prg_coverage(BankSize::Size1K),
+        Err(BankError::NotMultiple { rom_size: 100, bank_size: BankSize::Size1K })
+    );
+}
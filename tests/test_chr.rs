@@ -0,0 +1,155 @@
+mod common;
+
+use dotnes::{
+    chr::{self, Sheet, Tile},
+    palette::{Palette, PaletteError},
+    BankError, BankSize,
+};
+
+fn nes_with_chr(chr_rom: &[u8]) -> Vec<u8> {
+    common::nes_file(0, &[0; 16 * 1024], chr_rom)
+}
+
+#[test]
+fn chr_tiles_round_trip() {
+    let chr_rom: Vec<u8> = (0..8 * 1024).map(|i| (i * 7 % 251) as u8).collect();
+    let data = nes_with_chr(&chr_rom);
+    let nes = dotnes::parse(&data).unwrap();
+
+    let banks: Vec<_> = nes.chr_banks(BankSize::Size4K).unwrap().collect();
+    assert_eq!(banks.len(), 2);
+    assert_eq!(banks[1].tiles().len(), 256);
+
+    let tiles: Vec<Tile> = banks.iter().flat_map(|bank| bank.tiles()).collect();
+    assert_eq!(tiles.len(), 512);
+    assert!(tiles.iter().flat_map(|tile| tile.pixels.iter().flatten()).all(|&x| x < 4));
+    assert_eq!(chr::encode(&tiles), chr_rom);
+
+    // planes of the first two rows are 0x00, 0x07 and 0x38, 0x3F
+    assert_eq!(tiles[0].pixels[0], [0, 0, 2, 2, 2, 0, 0, 0]);
+    assert_eq!(tiles[0].pixels[1], [0, 0, 2, 2, 2, 3, 3, 3]);
+
+    // modify a tile and write it back
+    let mut chr_rom = nes.chr_rom.to_vec();
+    let mut tile = tiles[300];
+    tile.pixels[7][7] = 3;
+    chr::write_tile(&mut chr_rom, 300, &tile).unwrap();
+    assert_eq!(chr::tiles(&chr_rom).nth(300), Some(tile));
+    assert_eq!(chr_rom[300 * 16 + 7] & 1, 1);
+    assert_eq!(chr_rom[300 * 16 + 15] & 1, 1);
+    assert_eq!(chr::tiles(&chr_rom).rev().nth(511 - 299), Some(tiles[299]));
+}
+
+#[test]
+fn render_pattern_tables() {
+    let mut chr_rom = vec![0; 8 * 1024];
+    // tile 17 of the first table is solid color 1, tile 0 of the second is solid color 2
+    chr_rom[17 * 16..17 * 16 + 8].fill(0xFF);
+    chr_rom[0x1008..0x1010].fill(0xFF);
+
+    let sheets = chr::pattern_tables(&chr_rom);
+    assert_eq!(sheets.len(), 2);
+    assert_eq!((sheets[0].width, sheets[0].height), (128, 128));
+    assert_eq!(sheets[0].pixels[8 * 128 + 8..8 * 128 + 16], [1; 8]);
+    assert_eq!(sheets[0].pixels[8 * 128 + 16], 0);
+    assert_eq!(sheets[1].pixels[7 * 128 + 7], 2);
+
+    let mut pal: Vec<u8> = (0..192).map(|x| x as u8).collect();
+    let palette = Palette::from_pal(&pal).unwrap();
+    assert_eq!(palette.color(0x41), [3, 4, 5]);
+    let rgb = sheets[0].to_rgb(&palette.select([0x0F, 0x01, 0x02, 0x03]));
+    assert_eq!(rgb[..3], [45, 46, 47]);
+    assert_eq!(rgb[(8 * 128 + 8) * 3..(8 * 128 + 8) * 3 + 3], [3, 4, 5]);
+
+    pal.push(0);
+    assert_eq!(Palette::from_pal(&pal), Err(PaletteError::InvalidSize { size: 193 }));
+
+    // partial table and custom layout
+    let sheet = Sheet::new(chr::tiles(&chr_rom[..3 * 16]), 2);
+    assert_eq!((sheet.width, sheet.height), (16, 16));
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn write_pattern_table_png() {
+    let mut chr_rom = vec![0; 4 * 1024];
+    chr_rom[8..16].fill(0xFF);
+    let sheet = Sheet::pattern_table(&chr_rom);
+    let colors = Palette::NTSC.select([0x0F, 0x16, 0x27, 0x30]);
+
+    let mut png = Vec::new();
+    sheet.write_png(&colors, &mut png).unwrap();
+
+    let imported = Sheet::read_png(std::io::Cursor::new(&png), &colors).unwrap();
+    assert_eq!(imported, sheet);
+
+    let mut reader = png::Decoder::new(std::io::Cursor::new(png)).read_info().unwrap();
+    let info = reader.info();
+    assert_eq!((info.width, info.height), (128, 128));
+    assert_eq!(info.palette.as_deref(), Some(&colors.concat()[..]));
+    let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+    reader.next_frame(&mut pixels).unwrap();
+    assert_eq!(pixels, sheet.pixels);
+}
+
+#[test]
+fn import_chr_bank() {
+    let chr_rom: Vec<u8> = (0..8 * 1024).map(|i| (i * 13 % 256) as u8).collect();
+    let data = nes_with_chr(&chr_rom);
+    let nes = dotnes::parse(&data).unwrap();
+
+    // render the second table and import it back with slightly different colors
+    let colors = Palette::NTSC.select([0x0F, 0x16, 0x27, 0x30]);
+    let sheet = Sheet::pattern_table(&chr_rom[0x1000..]);
+    let rgb: Vec<u8> = sheet.to_rgb(&colors).iter().map(|x| x.saturating_sub(3)).collect();
+    let imported = Sheet::from_rgb(128, 128, &rgb, &colors);
+    assert_eq!(imported, sheet);
+    assert_eq!(imported.to_chr(), chr_rom[0x1000..]);
+
+    let mut tile = imported.tiles()[0];
+    tile.pixels[0][0] = 3;
+    let mut edited = imported.clone();
+    edited.pixels[0] = 3;
+    assert_eq!(edited.tiles()[0], tile);
+
+    let bytes = chr::replace_bank(&data, &nes, BankSize::Size4K, 0, &edited.to_chr()).unwrap();
+    let new = dotnes::parse(&bytes).unwrap();
+    assert_eq!(new.header, nes.header);
+    assert_eq!(new.prg_rom, nes.prg_rom);
+    assert_eq!(new.chr_rom[..0x1000], edited.to_chr()[..]);
+    assert_eq!(new.chr_rom[0x1000..], chr_rom[0x1000..]);
+
+    assert_eq!(
+        chr::replace_bank(&data, &nes, BankSize::Size4K, 2, &edited.to_chr()),
+        Err(BankError::OutOfRange { index: 2, count: 2 })
+    );
+    assert_eq!(
+        chr::replace_bank(&data, &nes, BankSize::Size8K, 0, &edited.to_chr()),
+        Err(BankError::WrongSize { size: 0x1000, bank_size: BankSize::Size8K })
+    );
+}
+
+#[test]
+fn replace_bank_keeps_other_bytes() {
+    let chr_rom: Vec<u8> = (0..8 * 1024).map(|i| (i * 13 % 256) as u8).collect();
+    let mut data = nes_with_chr(&chr_rom);
+    // iNES byte 8 of 0 means 8KB PRG-RAM, header writer would normalize it to 1
+    data[7] = 0;
+    data[8] = 0;
+    data[10] = 0x20;
+    data.extend_from_slice(b"trailing");
+    let nes = dotnes::parse(&data).unwrap();
+
+    let sheet = Sheet::pattern_table(&nes.chr_rom[0x1000..]);
+    let bytes = chr::replace_bank(&data, &nes, BankSize::Size4K, 1, &sheet.to_chr()).unwrap();
+    assert_eq!(bytes, data);
+    assert_ne!(nes.to_bytes(), data);
+
+    // data must be the bytes the file is parsed from
+    let chr = sheet.to_chr();
+    let headerless = &data[16..];
+    assert_eq!(
+        chr::replace_bank(headerless, &nes, BankSize::Size4K, 1, &chr),
+        Err(BankError::FileMismatch { size: data.len() - 16, file_len: data.len() })
+    );
+}
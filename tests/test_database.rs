@@ -0,0 +1,175 @@
+mod common;
+
+use {
+    common::ROM,
+    dotnes::{
+        database::{Database, DatabaseError, GameName},
+        header::{ConsoleType, Header, Mirroring, Timing, VsHardwareType, VsInfo, VsPPUType},
+    },
+    std::fs,
+};
+
+#[test]
+fn header_bytes_round_trip() {
+    let mut header = Header::new(4, 256 * 1024, 128 * 1024);
+    header.sub_mapper = 1;
+    header.prg_nvram_size = 8 * 1024;
+    header.has_persistent_memory = true;
+    header.mirroring = Mirroring::Vertical;
+    header.timing = Timing::PAL;
+    header.console_type = ConsoleType::Vs(VsInfo {
+        ppu_type: VsPPUType::RC2C0502,
+        hardware_type: VsHardwareType::DualSystemNormal,
+    });
+    let bytes = header.to_bytes();
+    assert_eq!(&bytes[..4], b"NES\x1A");
+
+    let mut file = bytes.to_vec();
+    file.resize(16 + 384 * 1024, 0);
+    assert_eq!(dotnes::parse(&file).unwrap().header, header);
+
+    // exponent-multiplier notation
+    header.prg_rom_size = 3 * 8 * 1024;
+    header.chr_rom_size = 0;
+    header.chr_ram_size = 8 * 1024;
+    let mut file = header.to_bytes().to_vec();
+    assert_eq!(file[9] & 0x0F, 0x0F);
+    file.resize(16 + 24 * 1024, 0);
+    assert_eq!(dotnes::parse(&file).unwrap().header, header);
+}
+
+#[test]
+fn assemble_from_nes20db() {
+    let data = fs::read(ROM).unwrap();
+    let prg_rom = &data[16..16 + 16 * 1024];
+    let crc = dotnes::crc32(prg_rom);
+
+    let xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+        <nes20db date="2020-01-01">
+        <game>
+            <!-- Other &amp; Game -->
+            <prgrom size="32768" crc32="00000000"/>
+            <pcb mapper="0" submapper="0" mirroring="V" battery="0"/>
+        </game>
+        <game>
+            <!-- Branch Basics -->
+            <prgrom size="16384" crc32="{crc:08X}"/>
+            <prgram size="8192"/>
+            <chrram size="8192"/>
+            <pcb mapper="0" submapper="0" mirroring="V" battery="0"/>
+            <console type="0" region="2"/>
+        </game>
+        </nes20db>"#
+    );
+    let database = Database::from_nes20db(&xml).unwrap();
+    assert_eq!(database.entries.len(), 2);
+
+    let entry = database.find(prg_rom, &[]).unwrap();
+    assert_eq!(entry.name.as_deref(), Some("Branch Basics"));
+    let bytes = database.assemble(prg_rom, &[]).unwrap();
+    let nes = dotnes::parse(&bytes).unwrap();
+    assert_eq!(nes.header.mirroring, Mirroring::Vertical);
+    assert_eq!(nes.header.timing, Timing::MultipleRegion);
+    assert_eq!(nes.header.prg_ram_size, 8 * 1024);
+    assert_eq!(Some(&nes.header), entry.header.as_ref());
+
+    assert!(database.assemble(&prg_rom[1..], &[]).is_none());
+    assert_eq!(
+        Database::from_nes20db("<game><pcb mapper=\"x\"/></game>"),
+        Err(DatabaseError::InvalidValue { attribute: "mapper", value: "x".to_owned() })
+    );
+    assert_eq!(Database::from_nes20db("<game><pcb"), Err(DatabaseError::Syntax { offset: 10 }));
+}
+
+#[test]
+fn assemble_from_dat() {
+    let data = fs::read(ROM).unwrap();
+    let prg_rom = &data[16..16 + 16 * 1024];
+    let crc = dotnes::crc32(prg_rom);
+
+    let header = "4E 45 53 1A 01 00 01 08 00 00 00 00 00 00 00 00";
+    let xml = format!(
+        r#"<?xml version="1.0"?>
+        <!DOCTYPE datafile PUBLIC "-//Logiqx//DTD ROM Management Datafile//EN"
+            "http://www.logiqx.com/Dats/datafile.dtd">
+        <datafile>
+            <header><name>Nintendo - NES</name></header>
+            <game name="No Header">
+                <rom name="No Header.nes" size="16384" crc="{crc:08x}"/>
+            </game>
+            <game name="Branch Basics (World)">
+                <description>Branch Basics (World)</description>
+                <rom name="Branch Basics (World).nes" size="16384" crc="{crc:08x}"
+                    header="{header}"/>
+            </game>
+        </datafile>"#
+    );
+    let database = Database::from_dat(&xml).unwrap();
+    assert_eq!(database.find(prg_rom, &[]).unwrap().name.as_deref(), Some("No Header"));
+
+    let bytes = database.assemble(prg_rom, &[]).unwrap();
+    let nes = dotnes::parse(&bytes).unwrap();
+    assert_eq!(nes.header.mirroring, Mirroring::Vertical);
+    assert!(nes.header.is_nes2);
+    assert_eq!(nes.prg_rom, prg_rom);
+}
+
+#[test]
+fn identify_by_dat() {
+    let data = fs::read(ROM).unwrap();
+    let nes = dotnes::parse(&data).unwrap();
+    let crc = dotnes::crc32(nes.prg_rom);
+    let sha1: String = dotnes::sha1(nes.prg_rom).iter().map(|x| format!("{x:02x}")).collect();
+
+    let xml = format!(
+        r#"<datafile>
+            <game name="Wrong SHA-1 (USA)">
+                <rom name="a.nes" size="16384" crc="{crc:08X}" sha1="{}"/>
+            </game>
+            <game name="Branch Basics (Japan, USA) (Rev 1) (Proto)">
+                <rom name="b.nes" size="16384" crc="{crc:08X}" sha1="{sha1}"/>
+            </game>
+        </datafile>"#,
+        "0".repeat(40)
+    );
+    let database = Database::from_dat(&xml).unwrap();
+    let name = database.identify(&nes).unwrap().game_name().unwrap();
+    assert_eq!(name.title, "Branch Basics");
+    assert_eq!(name.regions, ["Japan", "USA"]);
+    assert_eq!(name.revision.as_deref(), Some("Rev 1"));
+    assert_eq!(name.tags, ["Proto"]);
+
+    // header doesn't matter
+    let mut headered = data.clone();
+    headered[6] |= 0x01;
+    assert!(database.identify(&dotnes::parse(&headered).unwrap()).is_some());
+
+    // trailing bytes are ignored, but data is not
+    headered.push(0);
+    assert!(database.identify(&dotnes::parse(&headered).unwrap()).is_some());
+    headered[16] ^= 0xFF;
+    assert!(database.identify(&dotnes::parse(&headered).unwrap()).is_none());
+
+    // headered DATs hash the whole file
+    let xml = format!(
+        r#"<datafile>
+            <game name="Branch Basics (World)">
+                <rom name="a.nes" size="{}" crc="{:08X}"/>
+            </game>
+        </datafile>"#,
+        data.len(),
+        dotnes::crc32(&data)
+    );
+    let database = Database::from_dat(&xml).unwrap();
+    assert!(database.identify(&nes).is_none());
+    let entry = database.identify_bytes(&data).unwrap();
+    assert_eq!(entry.name.as_deref(), Some("Branch Basics (World)"));
+    assert!(database.identify_bytes(&headered).is_none());
+
+    let name = GameName::parse("Zelda no Densetsu (J) (V1.1) [T+Eng1.0_Zelda] [b1]");
+    assert_eq!(name.regions, ["Japan"]);
+    assert_eq!(name.revision.as_deref(), Some("V1.1"));
+    assert_eq!(name.flags, ["T+Eng1.0_Zelda", "b1"]);
+    assert_eq!(GameName::parse("Tetris (Unl)").tags, ["Unl"]);
+}
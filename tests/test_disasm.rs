@@ -0,0 +1,153 @@
+mod common;
+
+use {
+    common::ROM,
+    dotnes::disasm::{self, AddressingMode, ByteKind, Instruction, Opcode},
+    std::fs,
+};
+
+#[test]
+fn decode_all_opcodes() {
+    let official = (0..=255).filter(|&x| Opcode::decode(x).official).count();
+    assert_eq!(official, 151);
+
+    for byte in 0..=255 {
+        let instruction = Instruction::decode(&[byte, 0x34, 0x12], 0x8000).unwrap();
+        assert_eq!(instruction.bytes(), [byte, 0x34, 0x12][..instruction.size()]);
+    }
+
+    let decode = |bytes: &[u8]| Instruction::decode(bytes, 0x8000).unwrap().to_string();
+    assert_eq!(decode(&[0x0A]), "asl a");
+    assert_eq!(decode(&[0x6C, 0xFC, 0xFF]), "jmp ($FFFC)");
+    assert_eq!(decode(&[0xB1, 0x10]), "lda ($10),y");
+    assert_eq!(decode(&[0xA1, 0x10]), "lda ($10,x)");
+    assert_eq!(decode(&[0xB6, 0x10]), "ldx $10,y");
+    assert_eq!(decode(&[0xBD, 0x00, 0x03]), "lda $0300,x");
+    assert_eq!(decode(&[0x10, 0x80]), "bpl $7F82");
+    assert_eq!(Opcode::decode(0xEB).mnemonic, "SBC");
+    assert!(!Opcode::decode(0xEB).official);
+    assert_eq!(Opcode::decode(0x02).mnemonic, "JAM");
+    assert_eq!(Opcode::decode(0x9E).mode, AddressingMode::AbsoluteY);
+}
+
+#[test]
+fn disassemble_reset_routine() {
+    let data = fs::read(ROM).unwrap();
+    let nes = dotnes::parse(&data).unwrap();
+
+    let reset = u16::from_le_bytes([nes.prg_rom[0x3FFC], nes.prg_rom[0x3FFD]]);
+    let offset = usize::from(reset - 0xC000);
+    assert_eq!(disasm::cpu_address(&nes, offset), Some(reset));
+
+    let instructions: Vec<_> =
+        disasm::disassemble_prg(&nes, offset..nes.prg_rom.len()).unwrap().take(16).collect();
+    assert_eq!(instructions[0].address, reset);
+    for pair in instructions.windows(2) {
+        assert_eq!(pair[0].next_address(), pair[1].address);
+    }
+    assert!(disasm::disassemble_prg(&nes, 0..0x4001).is_none());
+
+    let source = disasm::to_ca65(&nes.prg_rom[offset..offset + 32], reset);
+    let lines: Vec<_> = source.lines().collect();
+    assert_eq!(lines[..3], [".org $E124", "    jsr $E0BB ; $E124", "    lda #$EA ; $E127"]);
+    assert_eq!(lines[8], "    jsr a:$0020 ; $E134");
+    // the last instruction is cut
+    assert_eq!(lines.last(), Some(&"    .byte $20 ; $E143"));
+}
+
+#[test]
+fn trace_test_rom() {
+    let data = fs::read(ROM).unwrap();
+    let nes = dotnes::parse(&data).unwrap();
+    let trace = disasm::trace(&nes);
+
+    // 16K PRG-ROM is mirrored at $8000 and $C000, the higher address is used
+    assert_eq!(trace.offset(0xA124), trace.offset(0xE124));
+    assert_eq!(trace.address(0x2124), Some(0xE124));
+
+    // `lda $E0E8` reads a table
+    let table = trace.offset(0xE0E8).unwrap();
+    assert_eq!(trace.kinds[table], ByteKind::Data);
+    // `jsr $0020` calls code written to RAM
+    assert!(trace.external.contains(&0x0020));
+
+    let code: usize = trace
+        .ranges()
+        .into_iter()
+        .filter(|(_, kind)| *kind == ByteKind::Code)
+        .map(|(range, _)| range.len())
+        .sum();
+    let instruction_bytes: usize = trace.instructions.values().map(Instruction::size).sum();
+    assert_eq!(code, instruction_bytes);
+
+    let source = trace.to_ca65(&nes);
+    assert!(source.starts_with(".org $C000\n"));
+    assert!(source.contains("LE124:\n    jsr LE0BB ; $E124\n"));
+    assert!(source.contains("    jsr a:$0020 ; $E134\n"));
+    assert!(source.ends_with("    .byte $B8, $E0, $24, $E1, $B4, $E0 ; $FFFA data\n"));
+}
+
+#[test]
+fn trace_fixed_bank() {
+    // UxROM with two banks, the last one is fixed at $C000
+    let mut prg = vec![0xFF; 32 * 1024];
+    let code = [
+        0x78, // $C000 sei
+        0xAD, 0x10, 0xC0, // $C001 lda $C010
+        0xF0, 0x03, // $C004 beq $C009
+        0x20, 0x00, 0x80, // $C006 jsr $8000, switchable bank
+        0x6C, 0x12, 0xC0, // $C009 jmp ($C012)
+    ];
+    prg[0x4000..0x4000 + code.len()].copy_from_slice(&code);
+    prg[0x4012..0x4014].copy_from_slice(&[0x00, 0xC0]);
+    prg[0x7FFA..].copy_from_slice(&[0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0]);
+    let data = common::nes_file(2, &prg, &[]);
+    let nes = dotnes::parse(&data).unwrap();
+
+    let trace = disasm::trace(&nes);
+    assert_eq!(trace.instructions.len(), 5);
+    assert_eq!(trace.labels.iter().copied().collect::<Vec<_>>(), [0x4000, 0x4009]);
+    assert_eq!(trace.external.iter().copied().collect::<Vec<_>>(), [0x8000]);
+    assert_eq!(trace.offset(0x8000), None);
+    assert!(trace.kinds[..0x4000].iter().all(|&x| x == ByteKind::Unknown));
+    assert_eq!(trace.kinds[0x4010], ByteKind::Data);
+    assert_eq!(trace.kinds[0x4011], ByteKind::Unknown);
+    assert_eq!(trace.kinds[0x4012..0x4014], [ByteKind::Data; 2]);
+    assert_eq!(trace.kinds[0x400B], ByteKind::Code);
+
+    let source = trace.to_ca65(&nes);
+    let lines: Vec<_> = source.lines().collect();
+    assert_eq!(
+        lines[..9],
+        [
+            ".org $C000",
+            "LC000:",
+            "    sei ; $C000",
+            "    lda $C010 ; $C001",
+            "    beq LC009 ; $C004",
+            "    jsr $8000 ; $C006",
+            "LC009:",
+            "    jmp ($C012) ; $C009",
+            "    .byte $FF, $FF, $FF, $FF ; $C00C unknown",
+        ]
+    );
+}
+
+#[test]
+fn label_inside_instruction() {
+    // `bit` skips the `lda #$01` hidden in its operand, jumping there enters it
+    let mut prg = vec![0xFF; 16 * 1024];
+    prg[..6].copy_from_slice(&[0x2C, 0xA9, 0x01, 0x4C, 0x01, 0xC0]);
+    let data = common::nes_file(0, &prg, &[0; 8 * 1024]);
+    let nes = dotnes::parse(&data).unwrap();
+
+    let trace = disasm::trace_from(&nes, &[0xC000]);
+    // overlapping instructions are not decoded, but the target is still a label
+    assert_eq!(trace.instructions.keys().copied().collect::<Vec<_>>(), [0, 3]);
+    assert!(trace.labels.contains(&1));
+
+    let source = trace.to_ca65(&nes);
+    assert!(
+        source.contains("LC000:\nLC001 = * + 1\n    bit $01A9 ; $C000\n    jmp LC001 ; $C003\n")
+    );
+}
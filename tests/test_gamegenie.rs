@@ -0,0 +1,98 @@
+mod common;
+
+use {
+    common::ROM,
+    dotnes::gamegenie::{self, Code, GameGenieError},
+};
+
+/// UxROM with 4 banks, byte $0123 of each bank is its bank number modulo 2
+fn uxrom() -> Vec<u8> {
+    let mut prg_rom = vec![0xEA; 64 * 1024];
+    for (bank, prg) in prg_rom.chunks_mut(16 * 1024).enumerate() {
+        prg[0x0123] = bank as u8 % 2;
+    }
+    common::nes_file(2, &prg_rom, &[])
+}
+
+#[test]
+fn decode_and_encode() {
+    let code = Code::decode("sxiopo").unwrap();
+    assert_eq!(code, Code { address: 0x91D9, value: 0xAD, compare: None });
+    assert_eq!(code.to_string(), "SXIOPO");
+
+    let long = Code { address: 0xC123, value: 0x60, compare: Some(0x01) };
+    let text = long.encode();
+    assert_eq!(text.len(), 8);
+    // the third letter tells an 8-letter code
+    assert!("EOXUKSVN".contains(&text[2..3]));
+    assert_eq!(text.parse(), Ok(long));
+
+    for address in (0x8000..=0xFFFF).step_by(0x0111) {
+        for &value in &[0x00, 0x5A, 0xA5, 0xFF] {
+            for &compare in &[None, Some(0x00), Some(0x81), Some(0xFF)] {
+                let code = Code { address, value, compare };
+                assert_eq!(Code::decode(&code.encode()), Ok(code));
+            }
+        }
+    }
+
+    assert_eq!(Code::decode("SXIOP"), Err(GameGenieError::InvalidLength { length: 5 }));
+    assert_eq!(Code::decode("SXIOPOPOP"), Err(GameGenieError::InvalidLength { length: 9 }));
+    assert_eq!(Code::decode("SXIOPB"), Err(GameGenieError::InvalidLetter { letter: 'B' }));
+}
+
+#[test]
+fn resolve_offsets() {
+    let data = uxrom();
+    let nes = dotnes::parse(&data).unwrap();
+
+    // the last bank is fixed at $C000, so it's the only candidate
+    let code = Code { address: 0xC123, value: 0x60, compare: Some(0x01) };
+    assert_eq!(code.offsets(&nes), [0xC123]);
+    let code = Code { compare: None, ..code };
+    assert_eq!(code.offsets(&nes), [0xC123]);
+    let code = Code { compare: Some(0x00), ..code };
+    assert!(code.offsets(&nes).is_empty());
+
+    // the window at $8000 is switchable, compare matches banks 1 and 3
+    let code = Code { address: 0x8123, value: 0x60, compare: Some(0x01) };
+    assert_eq!(code.offsets(&nes), [0x4123, 0xC123]);
+    // 6-letter codes replace the byte in every bank
+    let code = Code { compare: None, ..code };
+    assert_eq!(code.offsets(&nes), [0x0123, 0x4123, 0x8123, 0xC123]);
+
+    // 16KB NROM is mirrored, both addresses are the same byte
+    let test_rom = std::fs::read(ROM).unwrap();
+    let nes = dotnes::parse(&test_rom).unwrap();
+    let code = Code { address: 0xA128, value: 0, compare: None };
+    assert_eq!(code.offsets(&nes), [0x2128]);
+}
+
+#[test]
+fn apply_codes() {
+    let mut data = uxrom();
+    let codes = [
+        Code { address: 0xC123, value: 0x60, compare: Some(0x01) },
+        Code { address: 0x8000, value: 0x00, compare: Some(0xEA) },
+    ];
+    let offsets = gamegenie::apply(&mut data, &codes).unwrap();
+    assert_eq!(offsets, [0xC133, 0x10, 0x4010, 0x8010, 0xC010]);
+    assert_eq!(data[0xC133], 0x60);
+    // same byte in a switchable bank is not the fixed bank at $C000
+    assert_eq!(data[0x4133], 0x01);
+    assert_eq!(data[0x10], 0x00);
+
+    // nothing changes when a code doesn't match
+    let before = data.clone();
+    let codes = [
+        Code { address: 0x8001, value: 0x00, compare: None },
+        Code { address: 0xC123, value: 0x60, compare: Some(0x42) },
+    ];
+    assert_eq!(
+        gamegenie::apply(&mut data, &codes),
+        Err(GameGenieError::NoMatch { code: codes[1] })
+    );
+    assert_eq!(data, before);
+
+    assert!(matches!(gamegenie::apply(&mut data[..10], &codes), Err(GameGenieError::Parse(_))));
+}
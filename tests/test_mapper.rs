@@ -0,0 +1,280 @@
+mod common;
+
+use {
+    common::ROM,
+    dotnes::{
+        header::Lint,
+        mapper::{
+            self, BankState, EffectiveMirroring, Evidence, ExpansionAudio, MemoryMap,
+            MirroringControl, Mmc1Variant, NametableMirroring, Submapper, VrcVariant,
+        },
+        BankSize,
+    },
+    std::fs,
+};
+
+#[test]
+fn catalog_is_ordered_and_has_general_entries() {
+    let catalog = mapper::catalog();
+    for pair in catalog.windows(2) {
+        assert!(
+            (pair[0].mapper, pair[0].sub_mapper) < (pair[1].mapper, pair[1].sub_mapper),
+            "{} should be before {}",
+            pair[0].name,
+            pair[1].name
+        );
+    }
+    for info in catalog {
+        assert!(!info.name.is_empty());
+        assert!(catalog.iter().any(|x| x.mapper == info.mapper && x.sub_mapper.is_none()));
+    }
+}
+
+#[test]
+fn lookup_falls_back_to_general_entry() {
+    let mmc3 = mapper::lookup(4, 0).unwrap();
+    assert_eq!(mmc3.name, "MMC3");
+    assert!(mmc3.irq);
+    assert_eq!(mmc3.prg_bank_sizes, &[BankSize::Size8K]);
+    assert_eq!(mmc3.mirroring, MirroringControl::Switchable);
+
+    assert_eq!(mapper::lookup(4, 1).unwrap().name, "MMC6");
+    assert_eq!(mapper::lookup(24, 0).unwrap().expansion_audio, Some(ExpansionAudio::Vrc6));
+    assert!(!mapper::lookup(2, 1).unwrap().bus_conflicts);
+    assert!(mapper::lookup(2, 15).unwrap().bus_conflicts);
+    let bf9097 = mapper::lookup(71, 1).unwrap();
+    assert_eq!((bf9097.manufacturer, bf9097.bus_conflicts), ("Camerica", false));
+    assert!(mapper::lookup(4095, 0).is_none());
+}
+
+#[test]
+fn vrc_submapper_wiring() {
+    let data = fs::read(ROM).unwrap();
+    let mut header = dotnes::parse(&data).unwrap().header;
+
+    header.mapper = 23;
+    assert_eq!(header.submapper(), Submapper::Vrc(VrcVariant::Vrc2bOrVrc4eOrVrc4f));
+    header.sub_mapper = 2;
+    assert_eq!(header.submapper(), Submapper::Vrc(VrcVariant::Vrc4e));
+    assert_eq!(VrcVariant::Vrc4e.address_lines(), &[(2, 3)]);
+    assert_eq!(VrcVariant::Vrc4e.is_vrc4(), Some(true));
+
+    header.mapper = 1;
+    header.sub_mapper = 5;
+    assert_eq!(header.submapper(), Submapper::Mmc1(Mmc1Variant::FixedPrg));
+
+    header.mapper = 0;
+    assert_eq!(header.submapper(), Submapper::Unknown(5));
+}
+
+#[test]
+fn lint_header_against_mapper() {
+    let data = fs::read(ROM).unwrap();
+    let mut header = dotnes::parse(&data).unwrap().header;
+
+    header.mapper = 3;
+    header.is_four_screen = true;
+    header.has_persistent_memory = true;
+    assert_eq!(
+        header.lint(),
+        vec![Lint::ChrRamOnChrRomBoard, Lint::FourScreenUnsupported, Lint::BatteryWithoutPrgRam]
+    );
+
+    header.mapper = 0;
+    header.is_four_screen = false;
+    header.has_persistent_memory = false;
+    header.has_bus_conflicts = true;
+    assert_eq!(header.lint(), vec![Lint::UnexpectedBusConflicts]);
+
+    header.mapper = 4;
+    header.chr_rom_size = 128 * 1024;
+    header.is_four_screen = true;
+    header.has_persistent_memory = true;
+    assert_eq!(header.lint(), vec![Lint::UnexpectedBusConflicts]);
+
+    header.has_bus_conflicts = false;
+    header.is_nes2 = true;
+    header.prg_ram_size = 0;
+    header.prg_nvram_size = 2048;
+    assert_eq!(
+        header.lint(),
+        vec![Lint::PrgRamSizeMismatch { declared: 2048, known: &[8 * 1024] }]
+    );
+
+    header.mapper = 4095;
+    assert_eq!(header.lint(), vec![Lint::UnknownMapper { mapper: 4095 }]);
+}
+
+#[test]
+fn memory_map_translates_addresses() {
+    const KB: usize = 1024;
+
+    let nrom = MemoryMap::new(&BankState::Nrom, 16 * KB, 8 * KB);
+    assert_eq!(nrom.cpu_to_prg_rom(0xC123), Some(0x0123));
+    assert_eq!(nrom.prg_rom_to_cpu(0x0123), vec![0x8123, 0xC123]);
+    assert_eq!(nrom.ppu_to_chr_rom(0x1FFF), Some(0x1FFF));
+
+    let mut registers = [0, 2, 4, 5, 6, 7, 3, 1];
+    let mmc3 = MemoryMap::new(&BankState::Mmc3 { bank_select: 0x40, registers }, 128 * KB, 0);
+    assert_eq!(mmc3.cpu_to_prg_rom(0x8000), Some(112 * KB));
+    assert_eq!(mmc3.cpu_to_prg_rom(0xC001), Some(24 * KB + 1));
+    assert_eq!(mmc3.cpu_to_prg_rom(0xFFFC), Some(128 * KB - 4));
+    assert_eq!(mmc3.cpu_to_prg_rom(0x6000), None);
+    assert!(mmc3.chr.is_empty());
+
+    registers[0] = 9;
+    let mmc3 =
+        MemoryMap::new(&BankState::Mmc3 { bank_select: 0x80, registers }, 128 * KB, 128 * KB);
+    assert_eq!(mmc3.ppu_to_chr_rom(0x1000), Some(8 * KB));
+    assert_eq!(mmc3.ppu_to_chr_rom(0x0400), Some(5 * KB));
+
+    let mmc1 = MemoryMap::new(&BankState::power_on(1).unwrap(), 256 * KB, 0);
+    assert_eq!(mmc1.cpu_to_prg_rom(0xFFFA), Some(256 * KB - 6));
+    assert_eq!(mmc1.prg_rom_to_cpu(0), vec![0x8000]);
+
+    let addresses = |state: BankState| -> Vec<u16> {
+        state.fixed_prg(128 * KB).iter().map(|x| x.address).collect()
+    };
+    assert_eq!(addresses(BankState::UxRom { prg: 3 }), [0xC000]);
+    assert_eq!(addresses(BankState::power_on(4).unwrap()), [0xE000]);
+    assert_eq!(addresses(BankState::power_on(9).unwrap()), [0xA000, 0xC000, 0xE000]);
+    // MMC1 can switch the PRG mode, so its windows are never fixed
+    assert!(addresses(BankState::power_on(1).unwrap()).is_empty());
+    assert!(addresses(BankState::AxRom { prg: 0 }).is_empty());
+    let nrom = BankState::Nrom.fixed_prg(16 * KB);
+    assert_eq!(
+        nrom.iter().map(|x| (x.address, x.offset)).collect::<Vec<_>>(),
+        [(0x8000, 0), (0xC000, 0)]
+    );
+}
+
+#[test]
+fn mapper_emulation_follows_writes() {
+    let data = fs::read(ROM).unwrap();
+    let mut header = dotnes::parse(&data).unwrap().header;
+
+    header.mapper = 1;
+    let mut mmc1 = mapper::create(&header).unwrap();
+    for (i, bit) in [1, 0, 0, 1, 0].iter().enumerate() {
+        assert_eq!(mmc1.bank_state(), BankState::power_on(1).unwrap(), "write {i}");
+        mmc1.write(0xE000, *bit);
+    }
+    assert_eq!(mmc1.bank_state(), BankState::Mmc1 { control: 0x0C, chr0: 0, chr1: 0, prg: 9 });
+    assert_eq!(mmc1.mirroring(), NametableMirroring::SingleScreenLower);
+
+    header.mapper = 4;
+    let mut mmc3 = mapper::create(&header).unwrap();
+    mmc3.write(0x8000, 0x46);
+    mmc3.write(0x8001, 3);
+    mmc3.write(0xA000, 1);
+    assert_eq!(
+        mmc3.bank_state(),
+        BankState::Mmc3 { bank_select: 0x46, registers: [0, 2, 4, 5, 6, 7, 3, 1] }
+    );
+    assert_eq!(mmc3.mirroring(), NametableMirroring::Horizontal);
+    mmc3.write(0xC000, 2);
+    mmc3.write(0xC001, 0);
+    mmc3.write(0xE001, 0);
+    let irqs: Vec<_> = (0..4)
+        .map(|_| {
+            mmc3.clock_scanline();
+            mmc3.irq()
+        })
+        .collect();
+    assert_eq!(irqs, vec![false, false, true, true]);
+    mmc3.write(0xE000, 0);
+    assert!(!mmc3.irq());
+
+    header.mapper = 9;
+    let mut mmc2 = mapper::create(&header).unwrap();
+    mmc2.write(0xB000, 4);
+    mmc2.write(0xC000, 5);
+    assert_eq!(mmc2.bank_state(), BankState::Mmc2 { prg: 0, chr: [5, 0] });
+    mmc2.ppu_read(0x0FD8);
+    assert_eq!(mmc2.bank_state(), BankState::Mmc2 { prg: 0, chr: [4, 0] });
+
+    header.mapper = 69;
+    let mut fme7 = mapper::create(&header).unwrap();
+    fme7.write(0x8000, 0x0E);
+    fme7.write(0xA000, 1);
+    fme7.write(0x8000, 0x0D);
+    fme7.write(0xA000, 0x81);
+    fme7.clock_cpu();
+    assert!(!fme7.irq());
+    fme7.clock_cpu();
+    fme7.clock_cpu();
+    assert!(fme7.irq());
+
+    header.mapper = 2;
+    header.has_bus_conflicts = true;
+    let mut uxrom = mapper::create(&header).unwrap();
+    uxrom.write_rom(&[0x03; 0x8000], 0x8000, 0x07);
+    assert_eq!(uxrom.bank_state(), BankState::UxRom { prg: 3 });
+
+    header.mapper = 5;
+    assert!(mapper::create(&header).is_none());
+}
+
+#[test]
+fn effective_mirroring_by_mapper() {
+    use dotnes::header::Mirroring;
+
+    let data = fs::read(ROM).unwrap();
+    let mut header = dotnes::parse(&data).unwrap().header;
+    let mut effective = |mapper, four_screen, mirroring| {
+        header.mapper = mapper;
+        header.is_four_screen = four_screen;
+        header.mirroring = mirroring;
+        header.effective_mirroring()
+    };
+    let fixed = EffectiveMirroring::Fixed;
+
+    assert_eq!(effective(0, false, Mirroring::Vertical), fixed(NametableMirroring::Vertical));
+    assert_eq!(effective(4, true, Mirroring::Vertical), fixed(NametableMirroring::FourScreen));
+    assert_eq!(
+        effective(4, false, Mirroring::Vertical),
+        EffectiveMirroring::MapperControlled(MirroringControl::Switchable)
+    );
+    assert_eq!(
+        effective(30, true, Mirroring::HorizontalOrMapperControlled),
+        EffectiveMirroring::MapperControlled(MirroringControl::SingleScreen)
+    );
+    assert_eq!(effective(30, true, Mirroring::Vertical), fixed(NametableMirroring::FourScreen));
+    assert_eq!(effective(77, false, Mirroring::Vertical), fixed(NametableMirroring::FourScreen));
+    assert_eq!(
+        effective(218, true, Mirroring::HorizontalOrMapperControlled),
+        fixed(NametableMirroring::SingleScreenUpper)
+    );
+    assert_eq!(
+        effective(218, true, Mirroring::Vertical),
+        fixed(NametableMirroring::SingleScreenLower)
+    );
+}
+
+#[test]
+fn guess_mapper_of_headerless_rom() {
+    let with_code = |size: usize, code: &[u8]| {
+        let mut prg = vec![0xEA; size];
+        for chunk in prg.chunks_exact_mut(0x100).take(4) {
+            chunk[..code.len()].copy_from_slice(code);
+        }
+        let len = prg.len();
+        prg[len - 4..len - 2].copy_from_slice(&[0xF0, 0xFF]);
+        prg
+    };
+
+    // STA $E000, LSR A, STA $E000
+    let mmc1 = with_code(128 * 1024, &[0x8D, 0x00, 0xE0, 0x4A, 0x8D, 0x00, 0xE0]);
+    let guesses = mapper::guess(&mmc1, &[]);
+    assert_eq!(guesses[0].header.mapper, 1);
+    assert!(guesses[0].evidence.contains(&Evidence::SerialWrites));
+    assert_eq!(guesses[0].header.chr_ram_size, 8 * 1024);
+    let total: f64 = guesses.iter().map(|x| x.confidence).sum();
+    assert!((total - 1.0).abs() < 1e-9);
+
+    // STA $8000, LDA #6, STA $8001
+    let mmc3 = with_code(256 * 1024, &[0x8D, 0x00, 0x80, 0xA9, 0x06, 0x8D, 0x01, 0x80]);
+    let guesses = mapper::guess(&mmc3, &[0; 128 * 1024]);
+    assert_eq!(guesses[0].header.mapper, 4);
+    assert!(guesses.iter().all(|x| x.header.mapper != 0));
+}
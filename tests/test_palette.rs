@@ -0,0 +1,83 @@
+use dotnes::{
+    header::{ConsoleType, Header, Timing, VsInfo, VsPPUType},
+    palette::{Emphasis, EmphasisPalette, Palette, PaletteError},
+};
+
+#[test]
+fn palettes_by_ppu() {
+    assert_eq!(Palette::RP2C03.color(0x30), [255, 255, 255]);
+    assert_eq!(Palette::RP2C03.color(0x16), [255, 0, 0]);
+    assert_eq!(Palette::RP2C03.color(0x01), [0, 36, 146]);
+
+    // RP2C04 palettes are the same colors in different order
+    let sorted = |palette: &Palette| {
+        let mut colors = palette.colors.to_vec();
+        colors.sort_unstable();
+        colors
+    };
+    let rp2c04 =
+        [Palette::RP2C04_0001, Palette::RP2C04_0002, Palette::RP2C04_0003, Palette::RP2C04_0004];
+    for palette in &rp2c04[1..] {
+        assert_ne!(palette, &rp2c04[0]);
+        assert_eq!(sorted(palette), sorted(&rp2c04[0]));
+    }
+    assert_eq!(Palette::RP2C04_0001.color(0x02), Palette::RP2C03.color(0x16));
+
+    let mut header = Header::new(0, 0x8000, 0x2000);
+    assert_eq!(Palette::for_header(&header), Palette::NTSC);
+    assert_eq!(Emphasis::for_header(&header), Emphasis::Darken);
+    header.timing = Timing::PAL;
+    assert_eq!(Palette::for_header(&header), Palette::PAL);
+    header.timing = Timing::Dendy;
+    assert_eq!(Palette::for_header(&header), Palette::DENDY);
+    assert_eq!(Emphasis::for_header(&header), Emphasis::DarkenSwapped);
+    header.console_type =
+        ConsoleType::Vs(VsInfo { ppu_type: VsPPUType::RP2C040003, ..VsInfo::default() });
+    assert_eq!(Palette::for_header(&header), Palette::RP2C04_0003);
+    assert_eq!(Emphasis::for_header(&header), Emphasis::Saturate);
+    header.console_type = ConsoleType::PC10;
+    assert_eq!(Palette::for_header(&header), Palette::RP2C03);
+}
+
+#[test]
+fn composite_palettes_by_timing() {
+    let composite = [Palette::NTSC, Palette::PAL, Palette::DENDY];
+    for (i, palette) in composite.iter().enumerate() {
+        for other in &composite[i + 1..] {
+            assert_ne!(palette, other);
+        }
+        // grays and black are the same signal, only hues differ
+        assert_eq!(palette.color(0x0F), [0, 0, 0]);
+        assert_eq!(palette.color(0x20), palette.color(0x30));
+    }
+    // PAL colors differ in hue, not only in emphasis
+    for index in &[0x01, 0x12, 0x26, 0x3A] {
+        assert_ne!(Palette::PAL.color(*index), Palette::NTSC.color(*index), "color {index:02X}");
+    }
+}
+
+#[test]
+fn emphasis_and_pal_files() {
+    let ntsc = Palette::NTSC.emphasize(Emphasis::Darken);
+    let pal = Palette::NTSC.emphasize(Emphasis::DarkenSwapped);
+    assert_eq!(ntsc.base(), Palette::NTSC);
+    // PPUMASK bit 5 is red on NTSC and green on PAL
+    assert_eq!(ntsc.color(0x20, 0x30), pal.color(0x40, 0x30));
+    assert_eq!(ntsc.color(0x40, 0x30), pal.color(0x20, 0x30));
+    assert_eq!(ntsc.color(0x80, 0x30), pal.color(0x80, 0x30));
+    assert_eq!(ntsc.color(0xE0, 0x0F), [0, 0, 0]);
+
+    let rgb = Palette::RP2C03.emphasize(Emphasis::Saturate);
+    assert_eq!(rgb.color(0x80, 0x16), [255, 0, 255]);
+    assert_eq!(rgb.color(0xE0, 0x0F), [255, 255, 255]);
+
+    let file = rgb.to_pal();
+    assert_eq!(EmphasisPalette::from_pal(&file).unwrap(), rgb);
+    assert_eq!(Palette::from_pal(&file).unwrap(), Palette::RP2C03);
+    assert_eq!(Palette::from_pal(&Palette::RP2C03.to_pal()).unwrap(), Palette::RP2C03);
+    assert_eq!(
+        EmphasisPalette::from_pal(&file[..192]),
+        Err(PaletteError::InvalidSize { size: 192 })
+    );
+    assert_eq!(Palette::from_pal(&file[..100]), Err(PaletteError::InvalidSize { size: 100 }));
+}
@@ -1,14 +1,159 @@
-use {dotnes, std::fs, walkdir::WalkDir};
+mod common;
+
+use {
+    common::ROM,
+    dotnes::{
+        self, header::ExpansionDevice, BankError, BankSize, Missing, Overdump, ParseError,
+        ParseHeaderError, Section,
+    },
+    std::fs::{self, File},
+    walkdir::WalkDir,
+};
+
+fn all_valid_roms() -> impl Iterator<Item = walkdir::DirEntry> {
+    WalkDir::new("tests/roms").into_iter().filter_map(|entry| entry.ok()).filter(|entry| {
+        entry.file_type().is_file() && entry.path().extension().unwrap_or_default() == "nes"
+    })
+}
 
 #[test]
 fn parse_all_valid_roms() {
-    for file in
-        WalkDir::new("tests/roms").into_iter().filter_map(|entry| entry.ok()).filter(|entry| {
-            entry.file_type().is_file() && entry.path().extension().unwrap_or_default() == "nes"
-        })
-    {
+    for file in all_valid_roms() {
         let data = fs::read(file.path()).unwrap();
         let nes_file = dotnes::parse(&data).unwrap();
         println!("{:#?}", nes_file.header);
+        assert_eq!(nes_file.layout.file_len(), data.len());
+        assert_eq!(&data[nes_file.layout.prg_rom.clone()], nes_file.prg_rom);
+        assert_eq!(&data[nes_file.layout.chr_rom.clone()], nes_file.chr_rom);
+    }
+}
+
+#[test]
+fn parse_reader_same_as_parse() {
+    for file in all_valid_roms() {
+        let data = fs::read(file.path()).unwrap();
+        let nes_file = dotnes::parse(&data).unwrap();
+        let mut reader = dotnes::parse_reader(File::open(file.path()).unwrap()).unwrap();
+        assert_eq!(reader.header, nes_file.header);
+        assert_eq!(reader.layout, nes_file.layout);
+        assert_eq!(reader.prg_rom_range().start, nes_file.layout.prg_rom.start as u64);
+        assert_eq!(reader.miscellaneous_roms_range().end, nes_file.layout.file_len() as u64);
+        assert_eq!(reader.read_trainer().unwrap(), nes_file.trainer);
+        assert_eq!(reader.read_prg_rom().unwrap(), nes_file.prg_rom);
+        assert_eq!(reader.read_chr_rom().unwrap(), nes_file.chr_rom);
+        assert_eq!(reader.read_miscellaneous_roms().unwrap(), nes_file.miscellaneous_roms);
     }
 }
+
+#[test]
+fn parse_errors_point_to_problem() {
+    let data = fs::read(ROM).unwrap();
+
+    assert_eq!(
+        dotnes::parse(&data[..10]).unwrap_err(),
+        ParseError::Truncated { section: Section::Header, expected: 16, available: 10 }
+    );
+    assert_eq!(
+        dotnes::parse(&data[..data.len() - 100]).unwrap_err(),
+        ParseError::Truncated { section: Section::PrgRom, expected: 16384, available: 16384 - 100 }
+    );
+
+    let mut bad_magic = data.clone();
+    bad_magic[2] = b'X';
+    let err = dotnes::parse(&bad_magic).unwrap_err();
+    assert_eq!(err, ParseError::InvalidHeader(ParseHeaderError::MagicBytesNotMatch { index: 2 }));
+    assert_eq!(err.to_string(), "invalid header: byte 2 does not match NES magic bytes");
+}
+
+#[test]
+fn parse_nes2_header_fields() {
+    let mut input = *b"NES\x1A\x02\x03\x00\x08\x00\x21\x75\x89\x00\x00\x02\x05";
+    let header = dotnes::parse_recover(&input).unwrap().file.header;
+    assert!(header.is_nes2);
+    // byte 9: low nibble is PRG-ROM MSB, high nibble is CHR-ROM MSB
+    assert_eq!(header.prg_rom_size, 0x102 * 16 * 1024);
+    assert_eq!(header.chr_rom_size, 0x203 * 8 * 1024);
+    // byte 10 and 11: low nibble is volatile RAM, high nibble is non-volatile RAM
+    assert_eq!((header.prg_ram_size, header.prg_nvram_size), (64 << 5, 64 << 7));
+    assert_eq!((header.chr_ram_size, header.chr_nvram_size), (64 << 9, 64 << 8));
+    assert_eq!(header.miscellaneous_rom_count, 2);
+    // byte 15, not byte 14
+    assert_eq!(header.default_expansion_device, ExpansionDevice::VsSystemWithReversedInputs);
+
+    // exponent-multiplier notation, 2^4 * 3 and 2^63 * 7
+    input[9] = 0xFF;
+    input[4] = 0b0001_0001;
+    input[5] = 0xFF;
+    let err = dotnes::parse_recover(&input).unwrap_err();
+    assert_eq!(err, ParseError::InvalidHeader(ParseHeaderError::RomSizeOverflow { index: 5 }));
+    input[5] = 0b0001_0001;
+    let header = dotnes::parse_recover(&input).unwrap().file.header;
+    assert_eq!((header.prg_rom_size, header.chr_rom_size), (48, 48));
+}
+
+#[test]
+fn recover_truncated_and_overdumped() {
+    let data = fs::read(ROM).unwrap();
+
+    let truncated = dotnes::parse_recover(&data[..data.len() - 100]).unwrap();
+    assert_eq!(truncated.file.prg_rom.len(), 16384 - 100);
+    assert_eq!(
+        truncated.missing,
+        vec![Missing { section: Section::PrgRom, expected: 16384, available: 16384 - 100 }]
+    );
+
+    let mut overdumped = data[..16].to_vec();
+    overdumped[4] = 2;
+    overdumped.extend_from_slice(&data[16..]);
+    overdumped.extend_from_slice(&data[16..]);
+    let recovered = dotnes::parse_recover(&overdumped).unwrap();
+    assert!(recovered.missing.is_empty());
+    assert_eq!(
+        recovered.overdumps,
+        vec![Overdump { section: Section::PrgRom, declared: 32768, suggested: 16384 }]
+    );
+    assert_eq!(recovered.suggested_header(), dotnes::parse(&data).unwrap().header);
+}
+
+#[test]
+fn recover_stops_at_smallest_rom_size() {
+    let data = fs::read(ROM).unwrap();
+
+    // blank CHR-ROM and a PRG-ROM whose 8K halves are the same are not overdumps
+    let mut blank_chr = data[..16].to_vec();
+    blank_chr[5] = 2;
+    blank_chr.extend_from_slice(&data[16 + 8192..]);
+    blank_chr.extend_from_slice(&data[16 + 8192..]);
+    blank_chr.resize(blank_chr.len() + 16384, 0);
+    assert!(dotnes::parse_recover(&blank_chr).unwrap().is_good());
+
+    let mut overdumped_chr = blank_chr[..16 + 16384].to_vec();
+    overdumped_chr.extend_from_slice(&data[16 + 8192..]);
+    overdumped_chr.extend_from_slice(&data[16 + 8192..]);
+    let recovered = dotnes::parse_recover(&overdumped_chr).unwrap();
+    assert_eq!(
+        recovered.overdumps,
+        vec![Overdump { section: Section::ChrRom, declared: 16384, suggested: 8192 }]
+    );
+    assert_eq!(recovered.suggested_header().chr_rom_size, 8192);
+}
+
+#[test]
+fn split_banks() {
+    let data = fs::read(ROM).unwrap();
+    let nes = dotnes::parse(&data).unwrap();
+
+    let banks = nes.prg_banks(BankSize::Size8K).unwrap().collect::<Vec<_>>();
+    assert_eq!(banks.len(), 2);
+    assert_eq!(banks[1].offset(), 8192);
+    assert_eq!(banks[1], nes.last_prg_bank(BankSize::Size8K).unwrap());
+    assert_eq!(
+        nes.prg_banks(BankSize::Size32K).unwrap_err(),
+        BankError::NotMultiple { rom_size: 16384, bank_size: BankSize::Size32K }
+    );
+    assert_eq!(nes.last_chr_bank(BankSize::Size8K).unwrap_err(), BankError::Empty);
+
+    let mut reader = dotnes::parse_reader(File::open(ROM).unwrap()).unwrap();
+    assert_eq!(reader.read_prg_bank(BankSize::Size8K, 1).unwrap(), banks[1].data);
+    assert!(reader.read_prg_bank(BankSize::Size8K, 2).is_err());
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use dotnes::{
+    header::Header,
+    symbols::{Location, NlFile, Symbol, SymbolError, SymbolTable},
+};
+
+/// NROM with 32KB PRG-ROM and a trainer, so PRG-ROM starts at file offset $210
+fn nes_with_trainer() -> Vec<u8> {
+    let mut header = Header::new(0, 32 * 1024, 8 * 1024);
+    header.has_trainer = true;
+    common::nes_file_with_header(&header, &[0; 32 * 1024], &[0; 8 * 1024])
+}
+
+#[test]
+fn fceux_name_lists() {
+    let data = nes_with_trainer();
+    let nes = dotnes::parse(&data).unwrap();
+
+    let ram = SymbolTable::from_nl("$0300/10#buffer#Sprite buffer\n$2000#PPUCTRL#\n", NlFile::Ram)
+        .unwrap();
+    assert_eq!(ram.symbols[0].location, Location::Cpu(0x0300));
+    assert_eq!(ram.symbols[0].size, 16);
+    assert_eq!(ram.symbols[1].comment, None);
+
+    let text = "$C000#reset#Entry point\n\\second line\n$FFFA#vectors#\n";
+    let bank = SymbolTable::from_nl(text, NlFile::Bank(1)).unwrap();
+    let reset = bank.find("reset").unwrap();
+    assert_eq!(reset.location, Location::PrgRom { offset: 0x4000, address: Some(0xC000) });
+    assert_eq!(reset.comment.as_deref(), Some("Entry point\nsecond line"));
+    assert_eq!(reset.file_offset(&nes), Some(0x4210));
+    assert_eq!(bank.find("vectors").unwrap().prg_rom_offset(), Some(0x7FFA));
+
+    // continuation of a symbol without comment starts the comment
+    let nmi = SymbolTable::from_nl("$C000#nmi#\n\\Vblank\n", NlFile::Bank(1)).unwrap();
+    assert_eq!(nmi.symbols[0].comment.as_deref(), Some("Vblank"));
+
+    let mut all = ram.clone();
+    all.symbols.extend(bank.symbols);
+    // no CPU address, written at its power on address
+    all.symbols.push(Symbol {
+        name: "table".to_owned(),
+        location: Location::PrgRom { offset: 0x0100, address: None },
+        size: 1,
+        comment: None,
+    });
+    assert_eq!(
+        all.to_nl(&nes),
+        [
+            (NlFile::Ram, "$0300/10#buffer#Sprite buffer\n$2000#PPUCTRL#\n".to_owned()),
+            (NlFile::Bank(0), "$8100#table#\n".to_owned()),
+            (NlFile::Bank(1), text.to_owned()),
+        ]
+    );
+
+    assert_eq!(
+        SymbolTable::from_nl("C000#reset#", NlFile::Ram),
+        Err(SymbolError::Syntax { line: 1 })
+    );
+    assert_eq!(
+        SymbolTable::from_nl("\n$ZZZZ#x#", NlFile::Ram),
+        Err(SymbolError::Syntax { line: 2 })
+    );
+}
+
+#[test]
+fn mesen_labels() {
+    let text = "P:4000:reset:Entry point\\nsecond line\n\
+                R:0300-030F:buffer\n\
+                G:2000:PPUCTRL\n\
+                S:0010:save_slot:\n\
+                NesWorkRam:0020:scratch\n\
+                NesChrRom:0000:tiles\n";
+    let symbols = SymbolTable::from_mlb(text).unwrap();
+    assert_eq!(symbols.symbols.len(), 5);
+    assert_eq!(symbols.symbols[0].location, Location::PrgRom { offset: 0x4000, address: None });
+    assert_eq!(symbols.symbols[0].comment.as_deref(), Some("Entry point\nsecond line"));
+    assert_eq!(symbols.symbols[1].location, Location::Cpu(0x0300));
+    assert_eq!(symbols.symbols[1].size, 16);
+    assert_eq!(symbols.symbols[2].location, Location::Cpu(0x2000));
+    assert_eq!(symbols.symbols[3].location, Location::SaveRam(0x10));
+    assert_eq!(symbols.symbols[4].location, Location::WorkRam(0x20));
+
+    assert_eq!(
+        symbols.to_mlb(),
+        "P:4000:reset:Entry point\\nsecond line\n\
+         R:0300-030F:buffer\n\
+         G:2000:PPUCTRL\n\
+         S:0010:save_slot\n\
+         W:0020:scratch\n"
+    );
+    assert_eq!(SymbolTable::from_mlb("P:4000"), Err(SymbolError::Syntax { line: 1 }));
+    assert_eq!(SymbolTable::from_mlb("P:4000-3000:x"), Err(SymbolError::Syntax { line: 1 }));
+}
+
+#[test]
+fn ld65_debug_info() {
+    let data = nes_with_trainer();
+    let nes = dotnes::parse(&data).unwrap();
+
+    let text = r#"version	major=2,minor=0
+info	csym=0,file=1,lib=0,line=10,mod=1,scope=1,seg=4,span=10,sym=5,type=4
+file	id=0,name="main.s",size=1000,mtime=0x5F000000,mod=0
+seg	id=0,name="HEADER",start=0x000000,size=0x0010,addrsize=absolute,type=ro,oname="game.nes",ooffs=0
+seg	id=1,name="CODE",start=0x008000,size=0x0100,addrsize=absolute,type=ro,oname="game.nes",ooffs=528
+seg	id=2,name="VECTORS",start=0x00FFFA,size=0x0006,addrsize=absolute,type=ro,oname="game.nes",ooffs=33290
+seg	id=3,name="ZEROPAGE",start=0x000000,size=0x0010,addrsize=zeropage,type=rw
+sym	id=0,name="reset",addrsize=absolute,scope=0,def=1,ref=2,val=0x8010,seg=1,type=lab
+sym	id=1,name="nmi",addrsize=absolute,size=3,scope=0,def=3,val=0xFFFA,seg=2,type=lab
+sym	id=2,name="frame",addrsize=zeropage,scope=0,def=4,val=0x2,seg=3,type=lab
+sym	id=3,name="PPUCTRL",addrsize=absolute,scope=0,def=5,val=0x2000,type=equ
+sym	id=4,name="extern",addrsize=absolute,scope=0,ref=6,type=imp
+"#;
+    let symbols = SymbolTable::from_dbg(text, &nes).unwrap();
+    assert_eq!(symbols.symbols.len(), 3);
+    let reset = symbols.find("reset").unwrap();
+    assert_eq!(reset.location, Location::PrgRom { offset: 0x10, address: Some(0x8010) });
+    assert_eq!(reset.file_offset(&nes), Some(0x220));
+    let nmi = symbols.find("nmi").unwrap();
+    assert_eq!(nmi.location, Location::PrgRom { offset: 0x7FFA, address: Some(0xFFFA) });
+    assert_eq!(nmi.size, 3);
+    assert_eq!(symbols.find("frame").unwrap().location, Location::Cpu(0x0002));
+
+    // written debug info reads back to the same symbols
+    let written = symbols.to_dbg(&nes);
+    assert!(written.contains("start=0x008010,size=0x7FED,addrsize=absolute,type=ro,ooffs=544\n"));
+    assert_eq!(SymbolTable::from_dbg(&written, &nes), Ok(symbols));
+
+    let missing = "sym\tid=0,name=\"x\",val=0x8000,seg=7,type=lab\n";
+    assert_eq!(SymbolTable::from_dbg(missing, &nes), Err(SymbolError::UnknownSegment { id: 7 }));
+    assert_eq!(
+        SymbolTable::from_dbg("seg\tid=0,start", &nes),
+        Err(SymbolError::Syntax { line: 1 })
+    );
+}
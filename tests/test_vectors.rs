@@ -0,0 +1,86 @@
+mod common;
+
+use {
+    common::ROM,
+    dotnes::{Vector, VectorProblem, Vectors},
+};
+
+/// ROM with `banks` 16KB PRG banks, each bank ends with vectors pointing into itself
+fn nes_with_vectors(mapper: u16, banks: usize) -> Vec<u8> {
+    let mut prg_rom = vec![0; banks * 16 * 1024];
+    for (bank, prg) in prg_rom.chunks_mut(16 * 1024).enumerate() {
+        let high = 0xC0 + bank as u8;
+        prg[0x3FFA..].copy_from_slice(&[0x00, high, 0x10, high, 0x20, high]);
+    }
+    common::nes_file(mapper, &prg_rom, &[0; 8 * 1024])
+}
+
+#[test]
+fn vectors_of_test_rom() {
+    let data = std::fs::read(ROM).unwrap();
+    let nes = dotnes::parse(&data).unwrap();
+    let vectors = nes.vectors().unwrap();
+    assert_eq!(vectors.reset, 0xE124);
+    assert!(vectors.problems(&nes).is_empty());
+}
+
+#[test]
+fn vectors_from_fixed_bank() {
+    // UxROM and MMC1 both fix the last bank at $C000 at power on
+    for &mapper in &[1, 2] {
+        let data = nes_with_vectors(mapper, 4);
+        let nes = dotnes::parse(&data).unwrap();
+        let vectors = nes.vectors().unwrap();
+        assert_eq!(vectors, Vectors { nmi: 0xC300, reset: 0xC310, irq: 0xC320 });
+        assert!(vectors.problems(&nes).is_empty());
+    }
+
+    // unsupported mapper falls back to the end of PRG-ROM
+    let data = nes_with_vectors(200, 2);
+    let nes = dotnes::parse(&data).unwrap();
+    assert_eq!(nes.vectors().unwrap().reset, 0xC110);
+}
+
+#[test]
+fn vector_problems() {
+    let data = nes_with_vectors(0, 2);
+    let nes = dotnes::parse(&data).unwrap();
+
+    let vectors = Vectors { nmi: 0x6000, reset: 0x0000, irq: 0xFFFF };
+    let problems = vectors.problems(&nes);
+    assert_eq!(
+        problems,
+        [
+            VectorProblem::NotPrgRom { vector: Vector::Nmi, address: 0x6000 },
+            VectorProblem::Blank { vector: Vector::Reset, address: 0x0000 },
+            VectorProblem::Blank { vector: Vector::Irq, address: 0xFFFF },
+        ]
+    );
+    assert_eq!(problems[0].to_string(), "NMI vector $6000 is not in PRG-ROM");
+    assert_eq!(problems[1].to_string(), "RESET vector is $0000");
+
+    // unsupported mapper, everything from $8000 is PRG-ROM
+    let data = nes_with_vectors(200, 2);
+    let nes = dotnes::parse(&data).unwrap();
+    let vectors = Vectors { nmi: 0x8000, reset: 0x7FFF, irq: 0xC000 };
+    assert_eq!(
+        vectors.problems(&nes),
+        [VectorProblem::NotPrgRom { vector: Vector::Reset, address: 0x7FFF }]
+    );
+}
+
+#[test]
+fn audit_blank_reset_vector() {
+    let mut data = nes_with_vectors(0, 1);
+    let reset = data.len() - 8 * 1024 - 4;
+    data[reset..reset + 2].copy_from_slice(&[0xFF, 0xFF]);
+
+    let audit = dotnes::audit(vec![("a.nes".into(), data)], None);
+    assert_eq!(
+        audit.issues,
+        [dotnes::Issue::BadResetVector {
+            path: "a.nes".into(),
+            problem: VectorProblem::Blank { vector: Vector::Reset, address: 0xFFFF },
+        }]
+    );
+}